@@ -0,0 +1,29 @@
+use std::{hint::black_box, time::Instant};
+
+use filesan::{Mode, allowed, allowed_str};
+
+const ITERS: usize = 100_000;
+
+fn bench(name: &str, mut f: impl FnMut() -> bool) {
+    let start = Instant::now();
+    for _ in 0..ITERS {
+        black_box(f());
+    }
+    println!("{name}: {:?}/iter", start.elapsed() / ITERS as u32);
+}
+
+fn main() {
+    let ascii = "some_rather/long:file name with.multiple.dots.txt".repeat(4);
+    let unicode = "žluťoučký kůň úpěl ďábelské ódy.txt".repeat(4);
+
+    for (name, s) in [("ascii", &ascii), ("unicode", &unicode)] {
+        bench(&format!("allowed_str {name}"), || {
+            allowed_str(black_box(s), black_box(Mode::UNIX))
+        });
+        bench(&format!("chars + allowed {name}"), || {
+            black_box(s)
+                .chars()
+                .all(|c| allowed(c, black_box(Mode::UNIX)))
+        });
+    }
+}
@@ -0,0 +1,39 @@
+use std::{hint::black_box, time::Instant};
+
+use filesan::{Mode, Sanitizer};
+
+/// Generate names where about 90 % of them are repeats of the same few names.
+fn workload(n: usize) -> Vec<String> {
+    let mut x = 1u32;
+    let mut rand = move || {
+        x = x.wrapping_mul(1103515245).wrapping_add(12345);
+        x >> 8
+    };
+    (0..n)
+        .map(|i| {
+            if rand() % 10 == 0 {
+                format!("unique log file:{i} with some name.txt")
+            } else {
+                format!("repeated log file:{} name.txt", rand() % 16)
+            }
+        })
+        .collect()
+}
+
+fn main() {
+    let names = workload(100_000);
+    let san = Sanitizer::new().mode(Mode::WINDOWS);
+
+    let start = Instant::now();
+    for name in &names {
+        black_box(san.escape(black_box(name)));
+    }
+    println!("Sanitizer::escape: {:?}", start.elapsed());
+
+    let mut cached = san.cached(64);
+    let start = Instant::now();
+    for name in &names {
+        black_box(cached.escape(black_box(name)));
+    }
+    println!("CachedSanitizer::escape: {:?}", start.elapsed());
+}
@@ -0,0 +1,140 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Display;
+
+use crate::{Mode, escape_str, split_ext};
+
+/// What to do with names that have forbidden extension. See
+/// [`ExtBlocklist`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ExtPolicy {
+    /// Append the escape character to the escaped name (e.g. `setup.exe`
+    /// becomes `setup.exe_`). Escaped names never end with the escape
+    /// character, so renamed names never collide with other escaped names.
+    Rename,
+    /// Remove the forbidden extensions from the name (e.g. `setup.exe`
+    /// becomes `setup`). Different names may give the same result.
+    Strip,
+    /// Fail with [`ForbiddenExtension`].
+    Error,
+}
+
+/// Error returned by [`ExtBlocklist::escape`] with [`ExtPolicy::Error`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForbiddenExtension {
+    ext: String,
+}
+
+impl ForbiddenExtension {
+    /// Get the forbidden extension of the name (without the dot).
+    pub fn extension(&self) -> &str {
+        &self.ext
+    }
+}
+
+impl Display for ForbiddenExtension {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Forbidden extension `{}`.", self.ext)
+    }
+}
+
+impl core::error::Error for ForbiddenExtension {}
+
+/// List of forbidden extensions and policy of what to do with names that
+/// have them.
+///
+/// The extension is the part of the name after the last dot that is not at
+/// the start of the name, so `setup.exe.txt` has extension `txt`. Extensions
+/// are compared ASCII case insensitive.
+///
+/// # Example
+/// ```
+/// use filesan::{ExtBlocklist, ExtPolicy, Mode};
+///
+/// let list = ExtBlocklist::new(["exe", "dll", "lnk"], ExtPolicy::Rename);
+/// assert_eq!(
+///     list.escape("setup.EXE", '_', Mode::WINDOWS).unwrap(),
+///     "setup.EXE_"
+/// );
+/// assert_eq!(
+///     list.escape("setup.exe.txt", '_', Mode::WINDOWS).unwrap(),
+///     "setup.exe.txt"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtBlocklist {
+    exts: Vec<String>,
+    policy: ExtPolicy,
+}
+
+impl ExtBlocklist {
+    /// Create new blocklist with the given extensions. The extensions may be
+    /// given with or without the leading dot.
+    pub fn new<I>(exts: I, policy: ExtPolicy) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        Self {
+            exts: exts
+                .into_iter()
+                .map(|e| {
+                    let e = e.as_ref();
+                    e.strip_prefix('.').unwrap_or(e).into()
+                })
+                .collect(),
+            policy,
+        }
+    }
+
+    /// Get the policy.
+    pub fn policy(&self) -> ExtPolicy {
+        self.policy
+    }
+
+    /// Checks whether the name has forbidden extension.
+    pub fn is_forbidden(&self, name: &str) -> bool {
+        self.forbidden_ext(name).is_some()
+    }
+
+    /// Escape the name in the same way as [`crate::escape_str`] and apply the
+    /// policy if it has forbidden extension.
+    ///
+    /// # Errors
+    /// - [`ForbiddenExtension`] if the name has forbidden extension and the
+    ///   policy is [`ExtPolicy::Error`].
+    pub fn escape(
+        &self,
+        name: &str,
+        esc: char,
+        mode: Mode,
+    ) -> Result<String, ForbiddenExtension> {
+        let Some(ext) = self.forbidden_ext(name) else {
+            return Ok(escape_str(name, esc, mode));
+        };
+
+        match self.policy {
+            ExtPolicy::Rename => {
+                let mut res = escape_str(name, esc, mode);
+                res.push(esc);
+                Ok(res)
+            }
+            ExtPolicy::Strip => {
+                let mut stem = split_ext(name).0;
+                while self.is_forbidden(stem) {
+                    stem = split_ext(stem).0;
+                }
+                Ok(escape_str(stem, esc, mode))
+            }
+            ExtPolicy::Error => Err(ForbiddenExtension { ext: ext.into() }),
+        }
+    }
+
+    /// Get the extension of the name if it is forbidden.
+    fn forbidden_ext<'a>(&self, name: &'a str) -> Option<&'a str> {
+        let ext = split_ext(name).1.strip_prefix('.')?;
+        self.exts
+            .iter()
+            .any(|e| e.eq_ignore_ascii_case(ext))
+            .then_some(ext)
+    }
+}
@@ -0,0 +1,54 @@
+use alloc::vec::Vec;
+
+use crate::{Mode, Position, allowed_u8, reserved_len, trailing_start};
+
+/// Escape the given bytes so that they may be used as valid path on the given
+/// systems.
+///
+/// This is useful for filenames that are not valid UTF-8 (e.g. on unix). The
+/// escaping is the same as in [`crate::escape_str`], but it is done per byte.
+/// Bytes with value `0x80` or more are allowed unless the mode disallows all
+/// non ASCII characters (e.g. [`Mode::POSIX`]). Disallowed bytes and
+/// the escape byte are replaced with the escape byte followed by two
+/// uppercase hex digits.
+///
+/// If the input is valid UTF-8, `esc` is ASCII character and the mode allows
+/// non ASCII characters, the result is the same as the result of
+/// [`crate::escape_str`].
+///
+/// # Example
+/// ```
+/// use filesan::{escape_bytes, Mode};
+///
+/// assert_eq!(
+///     escape_bytes(b"\xFFhello/the_re.txt:.", b'_', Mode::WINDOWS),
+///     b"\xFFhello_2Fthe_5Fre.txt_3A_2E"
+/// );
+/// assert_eq!(escape_bytes(b"..", b'_', Mode::UNIX), b"_2E.");
+/// ```
+pub fn escape_bytes(p: &[u8], esc: u8, mode: Mode) -> Vec<u8> {
+    let reserved = reserved_len(p, mode);
+    let trailing = trailing_start(p, mode);
+    let mut res = Vec::with_capacity(p.len());
+
+    for (idx, &b) in p.iter().enumerate() {
+        let pos = Position {
+            idx,
+            reserved,
+            trailing,
+        };
+        if pos.reason(b == esc, allowed_u8(b, mode)).is_some() {
+            res.push(esc);
+            res.push(hex_digit(b >> 4));
+            res.push(hex_digit(b & 0xF));
+        } else {
+            res.push(b);
+        }
+    }
+
+    res
+}
+
+fn hex_digit(d: u8) -> u8 {
+    b"0123456789ABCDEF"[d as usize]
+}
@@ -0,0 +1,158 @@
+use alloc::{sync::Arc, vec::Vec};
+use std::collections::HashMap;
+
+use crate::Sanitizer;
+
+/// [`Sanitizer`] with cache of the recently escaped names.
+///
+/// Repeated names are not escaped again, but the cached result is returned.
+/// The cache has limited capacity and the least recently used names are
+/// removed when it is full.
+///
+/// # Example
+/// ```
+/// use filesan::{Mode, Sanitizer};
+///
+/// let mut san = Sanitizer::new().mode(Mode::WINDOWS).cached(100);
+/// for (name, expected) in [("a:b", "a_3Ab"), ("c", "c"), ("a:b", "a_3Ab")] {
+///     assert_eq!(&*san.escape(name), expected);
+/// }
+/// assert_eq!(san.len(), 2);
+/// assert_eq!(&*san.escape("a:b"), "a_3Ab");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CachedSanitizer {
+    sanitizer: Sanitizer,
+    capacity: usize,
+    map: HashMap<Arc<str>, usize>,
+    /// Cached entries. They form doubly linked list ordered from the most
+    /// recently used.
+    entries: Vec<Entry>,
+    /// The most recently used entry.
+    head: usize,
+    /// The least recently used entry.
+    tail: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    key: Arc<str>,
+    value: Arc<str>,
+    prev: usize,
+    next: usize,
+}
+
+/// Marks the end of the linked list.
+const NIL: usize = usize::MAX;
+
+impl Sanitizer {
+    /// Create sanitizer with cache that can hold at most `capacity` names.
+    pub fn cached(self, capacity: usize) -> CachedSanitizer {
+        CachedSanitizer::new(self, capacity)
+    }
+}
+
+impl CachedSanitizer {
+    /// Create new cached sanitizer that can hold at most `capacity` names.
+    pub fn new(sanitizer: Sanitizer, capacity: usize) -> Self {
+        Self {
+            sanitizer,
+            capacity,
+            map: HashMap::new(),
+            entries: Vec::new(),
+            head: NIL,
+            tail: NIL,
+        }
+    }
+
+    /// Get the configuration used for escaping.
+    pub fn sanitizer(&self) -> &Sanitizer {
+        &self.sanitizer
+    }
+
+    /// Get the maximum number of cached names.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of cached names.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if there are no cached names.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all the cached names.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.entries.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    /// Escape the given string. The result is the same as the result of
+    /// [`Sanitizer::escape`], but it is taken from the cache if the string
+    /// was escaped recently.
+    pub fn escape(&mut self, p: &str) -> Arc<str> {
+        if let Some(&i) = self.map.get(p) {
+            self.unlink(i);
+            self.push_front(i);
+            return self.entries[i].value.clone();
+        }
+
+        let value: Arc<str> = self.sanitizer.escape(p).into();
+        if self.capacity == 0 {
+            return value;
+        }
+
+        let key: Arc<str> = p.into();
+        let i = if self.entries.len() < self.capacity {
+            self.entries.push(Entry {
+                key: key.clone(),
+                value: value.clone(),
+                prev: NIL,
+                next: NIL,
+            });
+            self.entries.len() - 1
+        } else {
+            // Reuse the least recently used entry.
+            let i = self.tail;
+            self.unlink(i);
+            self.map.remove(&self.entries[i].key);
+            self.entries[i].key = key.clone();
+            self.entries[i].value = value.clone();
+            i
+        };
+        self.map.insert(key, i);
+        self.push_front(i);
+        value
+    }
+
+    fn unlink(&mut self, i: usize) {
+        let Entry { prev, next, .. } = self.entries[i];
+        if prev == NIL {
+            self.head = next;
+        } else {
+            self.entries[prev].next = next;
+        }
+        if next == NIL {
+            self.tail = prev;
+        } else {
+            self.entries[next].prev = prev;
+        }
+    }
+
+    fn push_front(&mut self, i: usize) {
+        self.entries[i].prev = NIL;
+        self.entries[i].next = self.head;
+        if self.head == NIL {
+            self.tail = i;
+        } else {
+            self.entries[self.head].prev = i;
+        }
+        self.head = i;
+    }
+}
@@ -1,9 +1,12 @@
+use alloc::string::String;
+use core::{fmt::Display, str::FromStr};
+
 use bitflags::bitflags;
 
 bitflags! {
     #[doc = "Escape flags for different systems. See documentation of"]
     #[doc = "[`crate::escape_str`] for more info."]
-    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
     pub struct Mode: u32 {
         #[doc = "No system specific escapes."]
         const NONE = 0x0;
@@ -11,15 +14,48 @@ bitflags! {
         const UNIX = 0x1;
         #[doc = "Escapes specific to windows."]
         const WINDOWS = 0x2;
-        #[doc = "Escapes specific to macos."]
+        #[doc = "Escapes specific to macos. This is for modern macOS, where"]
+        #[doc = "`/` is the path separator. See [`Mode::HFS`] for classic Mac"]
+        #[doc = "OS."]
         const MAC = 0x4;
-        #[doc = "Escapes for all systems combined."]
+        #[doc = "Escapes for all systems combined. This also includes"]
+        #[doc = "escaping of disallowed trailing characters on windows, so"]
+        #[doc = "it doesn't need to be combined with [`Mode::WINDOWS_END`]."]
         const ALL = 0x7;
         #[doc = "Disallowed characters at the end for windows. This is mostly"]
-        #[doc = "for internal use."]
+        #[doc = "for internal use. When used for escaping, the characters are"]
+        #[doc = "escaped everywhere, not only at the end."]
         const WINDOWS_END = 0x8;
+        #[doc = "POSIX portable filename character set. Only characters"]
+        #[doc = "`A-Za-z0-9._-` are allowed and the name must not start with"]
+        #[doc = "`-`. The names `.` and `..` are also reserved."]
+        const POSIX = 0x10;
+        #[doc = "Only ASCII characters are allowed. All characters with code"]
+        #[doc = "point `0x80` or larger are escaped. This is useful for"]
+        #[doc = "systems with limited unicode support."]
+        const ASCII_ONLY = 0x20;
+        #[doc = "Escapes for FAT32 and exFAT (with long names). The"]
+        #[doc = "disallowed characters, reserved names and disallowed"]
+        #[doc = "trailing characters are the same as with [`Mode::WINDOWS`],"]
+        #[doc = "so this contains [`Mode::WINDOWS`]. The names are also"]
+        #[doc = "limited to 255 UTF-16 code units, see"]
+        #[doc = "[`crate::escape_truncated_utf16`]."]
+        const FAT = 0x40 | Self::WINDOWS.bits();
+        #[doc = "Escapes for classic Mac OS and HFS, where `:` is the path"]
+        #[doc = "separator. Only `\\x00` and `:` are disallowed and `/` is"]
+        #[doc = "allowed. Use [`Mode::MAC`] for modern macOS (APFS or HFS+"]
+        #[doc = "through the POSIX API), where `/` is the separator. Use"]
+        #[doc = "this only for tools that use the legacy Mac APIs or that"]
+        #[doc = "write HFS volumes directly."]
+        const HFS = 0x80;
+        #[doc = "Escapes for names synchronized by cloud storage services"]
+        #[doc = "(OneDrive, SharePoint, Dropbox). This contains"]
+        #[doc = "[`Mode::WINDOWS`], so the windows rules apply. Names"]
+        #[doc = "starting with space or with `~$` and the names `.lock` and"]
+        #[doc = "`desktop.ini` are also reserved."]
+        const CLOUD = 0x100 | Self::WINDOWS.bits();
         #[doc = "Escapes specific for the current target system (unix)."]
-        #[cfg(unix)]
+        #[cfg(all(unix, not(target_os = "macos")))]
         const SYSTEM = Self::UNIX.bits();
         #[doc = "Escapes specific for the current target system (windows)."]
         #[cfg(windows)]
@@ -27,5 +63,133 @@ bitflags! {
         #[doc = "Escapes specific for the current target system (macos)."]
         #[cfg(target_os = "macos")]
         const SYSTEM = Self::MAC.bits();
+        #[doc = "Escapes for the current target system. The target has no"]
+        #[doc = "known file system (e.g. embedded), so this escapes for all"]
+        #[doc = "the supported systems ([`Mode::ALL`])."]
+        #[cfg(not(any(unix, windows)))]
+        const SYSTEM = Self::ALL.bits();
+    }
+}
+
+impl Mode {
+    /// Escapes for linux (unix) and macos.
+    pub const UNIX_MAC: Self = Self::UNIX.union(Self::MAC);
+    /// Escapes for linux (unix) and windows.
+    pub const UNIX_WINDOWS: Self = Self::UNIX.union(Self::WINDOWS);
+    /// Escapes for windows and macos.
+    pub const WINDOWS_MAC: Self = Self::WINDOWS.union(Self::MAC);
+}
+
+/// Error returned when parsing [`Mode`] from string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseModeError {
+    token: String,
+}
+
+impl ParseModeError {
+    /// Get the part of the string that is not valid mode name.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+impl Display for ParseModeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Unknown mode `{}`.", self.token)
+    }
+}
+
+impl core::error::Error for ParseModeError {}
+
+/// Names of the single flags of [`Mode`] as used in its string
+/// representation.
+const FLAG_NAMES: &[(&str, Mode)] = &[
+    ("fat", Mode::FAT),
+    ("cloud", Mode::CLOUD),
+    ("unix", Mode::UNIX),
+    ("windows", Mode::WINDOWS),
+    ("mac", Mode::MAC),
+    ("windows_end", Mode::WINDOWS_END),
+    ("posix", Mode::POSIX),
+    ("ascii_only", Mode::ASCII_ONLY),
+    ("hfs", Mode::HFS),
+];
+
+impl Display for Mode {
+    /// Display the set flags as lowercase names separated by `+` (e.g.
+    /// `unix+windows`). Empty mode is displayed as `none`. Unknown bits are
+    /// displayed as hex number. The result can be parsed back with
+    /// [`FromStr`].
+    ///
+    /// # Example
+    /// ```
+    /// use filesan::Mode;
+    ///
+    /// assert_eq!(Mode::NONE.to_string(), "none");
+    /// assert_eq!((Mode::UNIX | Mode::WINDOWS).to_string(), "unix+windows");
+    /// assert_eq!(Mode::ALL.to_string(), "unix+windows+mac");
+    /// ```
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_empty() {
+            return f.write_str("none");
+        }
+
+        let mut rest = *self;
+        let mut sep = "";
+        for (name, flag) in FLAG_NAMES {
+            // Composite flags may share bits.
+            if self.contains(*flag) && rest.intersects(*flag) {
+                write!(f, "{sep}{name}")?;
+                rest.remove(*flag);
+                sep = "+";
+            }
+        }
+        if !rest.is_empty() {
+            write!(f, "{sep}{:#x}", rest.bits())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Mode {
+    type Err = ParseModeError;
+
+    /// Parse mode from list of mode names separated by `,`, `|` or `+`. The
+    /// names are case insensitive and they may be surrounded by whitespace.
+    /// Valid names are `none`, `unix`, `windows`, `mac`, `all`, `windows_end`,
+    /// `posix`, `ascii_only`, `fat`, `hfs`, `cloud` and `system`. Other
+    /// bits may be given as hex number prefixed with `0x`.
+    ///
+    /// # Example
+    /// ```
+    /// use filesan::Mode;
+    ///
+    /// assert_eq!("windows".parse(), Ok(Mode::WINDOWS));
+    /// assert_eq!("Unix, mac".parse(), Ok(Mode::UNIX | Mode::MAC));
+    /// assert_eq!("unix|WINDOWS".parse(), Ok(Mode::UNIX | Mode::WINDOWS));
+    /// assert_eq!("unix+mac".parse(), Ok(Mode::UNIX | Mode::MAC));
+    /// assert!("linux".parse::<Mode>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split([',', '|', '+']).try_fold(Mode::NONE, |mode, t| {
+            let t = t.trim();
+            let flag = [
+                ("none", Mode::NONE),
+                ("all", Mode::ALL),
+                ("system", Mode::SYSTEM),
+            ]
+            .iter()
+            .chain(FLAG_NAMES)
+            .find(|(n, _)| n.eq_ignore_ascii_case(t))
+            .map(|(_, f)| *f)
+            .or_else(|| {
+                let hex = t.strip_prefix("0x")?;
+                u32::from_str_radix(hex, 16)
+                    .ok()
+                    .map(Mode::from_bits_retain)
+            })
+            .ok_or_else(|| ParseModeError { token: t.into() })?;
+            Ok(mode | flag)
+        })
     }
 }
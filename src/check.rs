@@ -0,0 +1,203 @@
+use alloc::vec::Vec;
+use core::{ops::Range, slice};
+
+use crate::{
+    Issue, Mode, allowed, invalid_chars, is_reserved, reserved_len,
+    trailing_start, validate_issues,
+};
+
+/// Names longer than this (in bytes) get [`WarningKind::TooLong`].
+const LONG_NAME: usize = 200;
+
+/// Severity of [`Finding`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The name is valid with the mode, but it may cause problems elsewhere.
+    Warning,
+    /// The name is not valid with the mode.
+    Error,
+}
+
+/// Kind of portability problem found by [`check`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// The character is allowed with the mode, but it is disallowed on other
+    /// systems (e.g. `:` with [`Mode::UNIX`]).
+    DisallowedElsewhere(char),
+    /// The name is reserved on other systems (e.g. `NUL` with
+    /// [`Mode::UNIX`]).
+    ReservedElsewhere,
+    /// The name starts with `-`, so command line programs may consider it to
+    /// be an option.
+    LeadingDash,
+    /// The name starts with space, which is easy to miss.
+    LeadingSpace,
+    /// The name ends with spaces or dots that are disallowed at the end of
+    /// the name on windows.
+    TrailingSpaceOrDot,
+    /// The name is longer than 200 bytes, which is close to the limit of
+    /// most filesystems.
+    TooLong,
+}
+
+/// Portability problem found by [`check`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Warning {
+    /// Kind of the problem.
+    pub kind: WarningKind,
+    /// Byte range of the name with the problem.
+    pub span: Range<usize>,
+}
+
+/// Single finding in [`Report`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Finding {
+    /// The name is not valid with the mode, [`crate::escape_str`] would
+    /// change it.
+    Error(Issue),
+    /// The name is valid, but it may cause problems elsewhere.
+    Warning(Warning),
+}
+
+impl Finding {
+    /// Get the severity of the finding.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::Error(_) => Severity::Error,
+            Self::Warning(_) => Severity::Warning,
+        }
+    }
+
+    /// Get the byte range of the name with the problem.
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            Self::Error(i) => i.span.clone(),
+            Self::Warning(w) => w.span.clone(),
+        }
+    }
+}
+
+/// Result of [`check`]. Errors are before warnings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    findings: Vec<Finding>,
+}
+
+impl Report {
+    /// Checks whether there are any errors.
+    pub fn has_errors(&self) -> bool {
+        self.error_count() != 0
+    }
+
+    /// Checks whether there are any warnings.
+    pub fn has_warnings(&self) -> bool {
+        self.warning_count() != 0
+    }
+
+    /// Checks whether there are no findings.
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Get the number of errors.
+    pub fn error_count(&self) -> usize {
+        self.iter()
+            .filter(|f| f.severity() == Severity::Error)
+            .count()
+    }
+
+    /// Get the number of warnings.
+    pub fn warning_count(&self) -> usize {
+        self.findings.len() - self.error_count()
+    }
+
+    /// Iterate over the findings.
+    pub fn iter(&self) -> slice::Iter<'_, Finding> {
+        self.findings.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Report {
+    type Item = &'a Finding;
+    type IntoIter = slice::Iter<'a, Finding>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Check the name for errors and portability problems.
+///
+/// Errors are the issues found by [`validate_issues`], so there are errors
+/// if the name is not valid with the mode. Warnings are reported for names
+/// that are valid, but that may cause problems later:
+/// - [`WarningKind::DisallowedElsewhere`]: characters disallowed by
+///   [`Mode::ALL`] but not by the mode.
+/// - [`WarningKind::ReservedElsewhere`]: names reserved by [`Mode::ALL`] but
+///   not by the mode.
+/// - [`WarningKind::LeadingDash`]: names starting with `-`.
+/// - [`WarningKind::LeadingSpace`]: names starting with space.
+/// - [`WarningKind::TrailingSpaceOrDot`]: names ending with spaces or dots.
+/// - [`WarningKind::TooLong`]: names longer than 200 bytes.
+///
+/// Warnings are not reported for the parts of the name that already have
+/// error of the same kind.
+///
+/// # Example
+/// ```
+/// use filesan::{check, Mode};
+///
+/// let report = check("-rf ", Mode::UNIX);
+/// assert!(!report.has_errors());
+/// assert_eq!(report.warning_count(), 2);
+///
+/// let report = check("a:b", Mode::ALL);
+/// assert!(report.has_errors());
+/// assert!(!report.has_warnings());
+/// ```
+pub fn check(name: &str, mode: Mode) -> Report {
+    let mut findings: Vec<_> = validate_issues(name, mode)
+        .err()
+        .unwrap_or_default()
+        .into_iter()
+        .map(Finding::Error)
+        .collect();
+
+    let mut warnings = Vec::new();
+    let mut warn = |kind, span| warnings.push(Warning { kind, span });
+
+    for (i, c) in invalid_chars(name, Mode::ALL) {
+        if allowed(c, mode) {
+            let kind = WarningKind::DisallowedElsewhere(c);
+            warn(kind, i..i + c.len_utf8());
+        }
+    }
+
+    let reserved = is_reserved(name, mode);
+    if !reserved && is_reserved(name, Mode::ALL) {
+        let len = reserved_len(name.as_bytes(), Mode::ALL);
+        warn(WarningKind::ReservedElsewhere, 0..len);
+    }
+    if !reserved && name.starts_with('-') {
+        warn(WarningKind::LeadingDash, 0..1);
+    }
+
+    if name.starts_with(' ') && allowed(' ', mode) {
+        let len = name.len() - name.trim_start_matches(' ').len();
+        warn(WarningKind::LeadingSpace, 0..len);
+    }
+
+    let p = name.as_bytes();
+    let trailing = trailing_start(p, Mode::WINDOWS);
+    if trailing != p.len() && trailing_start(p, mode) == p.len() {
+        warn(WarningKind::TrailingSpaceOrDot, trailing..p.len());
+    }
+
+    if name.len() > LONG_NAME {
+        warn(WarningKind::TooLong, 0..name.len());
+    }
+
+    warnings.sort_by_key(|w| w.span.start);
+    findings.extend(warnings.into_iter().map(Finding::Warning));
+    Report { findings }
+}
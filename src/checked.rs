@@ -0,0 +1,66 @@
+use alloc::string::String;
+use core::fmt::Display;
+
+use crate::{Mode, allowed, escape_str, reserved_len};
+
+/// Error returned by [`try_escape_str`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeError {
+    /// The escape character cannot be used with the given mode. It is either
+    /// disallowed, hex digit or names starting with it are reserved.
+    InvalidEscapeChar(char),
+}
+
+impl Display for EscapeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidEscapeChar(c) => {
+                write!(f, "Invalid escape character {c:?}.")
+            }
+        }
+    }
+}
+
+impl core::error::Error for EscapeError {}
+
+/// Escape the given string in the same way as [`crate::escape_str`], but
+/// check that the escape character is valid first.
+///
+/// Escape character that is disallowed with the given mode would produce
+/// invalid names. Escape character that is hex digit would make the escape
+/// sequences ambiguous. In both cases the result may not be unique. Names
+/// starting with the escape character must not be reserved (e.g. `-` with
+/// [`Mode::POSIX`]), because escaped reserved names start with it.
+///
+/// # Errors
+/// - [`EscapeError::InvalidEscapeChar`] if the escape character is not
+///   allowed with the given mode, if it is ASCII hex digit or if names
+///   starting with it are reserved.
+///
+/// # Example
+/// ```
+/// use filesan::{try_escape_str, EscapeError, Mode};
+///
+/// assert_eq!(try_escape_str("a:b", '_', Mode::MAC).unwrap(), "a_3Ab");
+/// assert_eq!(
+///     try_escape_str("a:b", '/', Mode::UNIX),
+///     Err(EscapeError::InvalidEscapeChar('/'))
+/// );
+/// assert_eq!(
+///     try_escape_str("-a", '-', Mode::POSIX),
+///     Err(EscapeError::InvalidEscapeChar('-'))
+/// );
+/// ```
+pub fn try_escape_str(
+    p: &str,
+    esc: char,
+    mode: Mode,
+) -> Result<String, EscapeError> {
+    if !allowed(esc, mode)
+        || esc.is_ascii_hexdigit()
+        || reserved_len(esc.encode_utf8(&mut [0; 4]).as_bytes(), mode) != 0
+    {
+        return Err(EscapeError::InvalidEscapeChar(esc));
+    }
+    Ok(escape_str(p, esc, mode))
+}
@@ -0,0 +1,99 @@
+use alloc::string::String;
+
+use crate::{
+    EscapedChar, Mode, allowed, is_reserved, unescape_str, write_escape,
+};
+
+/// Escape the fragment and append it to `dst`, so that the name may be built
+/// from multiple fragments.
+///
+/// The escape character and characters that are disallowed anywhere in the
+/// name are escaped in the same way as in [`crate::escape_str`]. Reserved
+/// names and characters that are disallowed at the end of the name depend on
+/// the whole name, so they are not handled here. Call [`finalize`] after the
+/// last fragment is pushed.
+///
+/// # Example
+/// ```
+/// use filesan::{finalize, push_escaped, Mode};
+///
+/// let mut name = String::new();
+/// push_escaped(&mut name, "CON", '_', Mode::WINDOWS);
+/// push_escaped(&mut name, "-", '_', Mode::WINDOWS);
+/// push_escaped(&mut name, "a:b", '_', Mode::WINDOWS);
+/// push_escaped(&mut name, ".json", '_', Mode::WINDOWS);
+/// finalize(&mut name, '_', Mode::WINDOWS);
+/// assert_eq!(name, "CON-a_3Ab.json");
+/// ```
+pub fn push_escaped(dst: &mut String, fragment: &str, esc: char, mode: Mode) {
+    for c in fragment.chars() {
+        if c == esc || !allowed(c, mode) {
+            _ = write_escape(dst, c, esc);
+        } else {
+            dst.push(c);
+        }
+    }
+}
+
+/// Fix the end of name built with [`push_escaped`], so that it is valid name.
+///
+/// If the whole name is reserved name, its first character is escaped.
+/// Characters that are disallowed at the end of the name are also escaped.
+/// No more fragments should be pushed after this.
+///
+/// The result can be unescaped with [`crate::unescape_str`] to get the
+/// concatenated fragments. With usual escape characters (e.g. `_`), the
+/// result is the same as the result of [`crate::escape_str`] on the
+/// concatenated fragments.
+///
+/// # Example
+/// ```
+/// use filesan::{finalize, push_escaped, Mode};
+///
+/// let mut name = String::new();
+/// push_escaped(&mut name, "CON", '_', Mode::WINDOWS);
+/// push_escaped(&mut name, ".ext", '_', Mode::WINDOWS);
+/// finalize(&mut name, '_', Mode::WINDOWS);
+/// assert_eq!(name, "_43ON.ext");
+///
+/// let mut name = String::new();
+/// push_escaped(&mut name, "a ", '_', Mode::WINDOWS);
+/// finalize(&mut name, '_', Mode::WINDOWS);
+/// assert_eq!(name, "a_20");
+/// ```
+pub fn finalize(dst: &mut String, esc: char, mode: Mode) {
+    // If the name starts with the escape character, the first character is
+    // already escaped. Characters of reserved name may be escaped if they are
+    // disallowed anywhere, so the name must be unescaped first.
+    if !dst.starts_with(esc)
+        && is_reserved(&unescape_str(dst, esc), mode)
+        && let Some(c) = dst.chars().next()
+    {
+        dst.replace_range(..c.len_utf8(), &EscapedChar::escaped(c, esc));
+    }
+
+    if !mode.intersects(Mode::WINDOWS) {
+        return;
+    }
+
+    // Characters disallowed at the end may already be escaped if they are
+    // disallowed anywhere, so escape sequences are also part of the end.
+    let trailing = [' ', '.'].map(|c| EscapedChar::escaped(c, esc));
+    let mut end = dst.len();
+    loop {
+        let s = &dst[..end];
+        if let Some(e) = trailing.iter().find(|e| s.ends_with(&***e)) {
+            end -= e.len();
+        } else if let Some(c) = s
+            .chars()
+            .next_back()
+            .filter(|c| !allowed(*c, Mode::WINDOWS_END))
+        {
+            end -= c.len_utf8();
+            let e = EscapedChar::escaped(c, esc);
+            dst.replace_range(end..end + c.len_utf8(), &e);
+        } else {
+            break;
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use core::{
+    fmt::{Debug, Display, Formatter, Write},
+    iter::FusedIterator,
+};
+
+use crate::{Classify, EscapedChar, Mode, write_escape};
+
+/// Lazily escaped string. It can be displayed without allocating the escaped
+/// string.
+///
+/// The displayed string is always the same as the result of
+/// [`crate::escape_str`] with the same arguments.
+///
+/// # Example
+/// ```
+/// use filesan::{Escaped, Mode};
+///
+/// let name = "the/file.";
+/// assert_eq!(
+///     format!("dir/{}", Escaped::new(name, '_', Mode::WINDOWS)),
+///     "dir/the_2Ffile_2E",
+/// );
+/// ```
+#[derive(Copy, Clone)]
+pub struct Escaped<'a> {
+    s: &'a str,
+    esc: char,
+    mode: Mode,
+}
+
+impl<'a> Escaped<'a> {
+    /// Create new lazily escaped string. See [`crate::escape_str`] for the
+    /// meaning of the arguments.
+    pub fn new(s: &'a str, esc: char, mode: Mode) -> Self {
+        Self { s, esc, mode }
+    }
+
+    fn write_to(&self, w: &mut impl Write) -> core::fmt::Result {
+        for (c, e) in Classify::new(self.s, self.esc, self.mode) {
+            if e {
+                write_escape(w, c, self.esc)?;
+            } else {
+                w.write_char(c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for Escaped<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.write_to(f)
+    }
+}
+
+impl Debug for Escaped<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_char('"')?;
+        self.write_to(&mut DebugWriter(f))?;
+        f.write_char('"')
+    }
+}
+
+/// Writes the characters in the same way as [`Debug`] for [`str`] would.
+struct DebugWriter<'a, 'b>(&'a mut Formatter<'b>);
+
+impl Write for DebugWriter<'_, '_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        s.chars().try_for_each(|c| self.write_char(c))
+    }
+
+    fn write_char(&mut self, c: char) -> core::fmt::Result {
+        if c == '\'' {
+            self.0.write_char(c)
+        } else {
+            write!(self.0, "{}", c.escape_debug())
+        }
+    }
+}
+
+/// Iterator over characters of escaped string. The characters are escaped
+/// lazily.
+///
+/// Collecting the iterator always gives the same result as
+/// [`crate::escape_str`] with the same arguments.
+#[derive(Clone)]
+pub struct EscapedChars<'a> {
+    cls: Classify<'a>,
+    esc: char,
+    /// Escape sequence whose characters are pending.
+    pending: EscapedChar,
+    /// Position of the next pending character in `pending`.
+    pos: usize,
+}
+
+/// Escape the given string lazily. Returns iterator over the characters of
+/// the escaped string.
+///
+/// See [`crate::escape_str`] for the meaning of the arguments.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_iter, Mode};
+///
+/// let short: String = escape_iter("the:file", '_', Mode::WINDOWS)
+///     .take(5)
+///     .collect();
+/// assert_eq!(short, "the_3");
+/// ```
+pub fn escape_iter(s: &str, esc: char, mode: Mode) -> EscapedChars<'_> {
+    EscapedChars {
+        cls: Classify::new(s, esc, mode),
+        esc,
+        pending: EscapedChar::escaped(esc, esc),
+        pos: usize::MAX,
+    }
+}
+
+impl Iterator for EscapedChars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(c) = self.pending_str().chars().next() {
+            self.pos += c.len_utf8();
+            return Some(c);
+        }
+
+        let (c, e) = self.cls.next()?;
+        if e {
+            self.pending = EscapedChar::escaped(c, self.esc);
+            self.pos = self.esc.len_utf8();
+            Some(self.esc)
+        } else {
+            Some(c)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Every character takes at most 4 bytes. The longest escape per byte
+        // is escape of single byte character that produces 3 characters.
+        let rem = self.cls.as_str().len();
+        let pending = self.pending_str().len();
+        (rem.div_ceil(4) + pending, Some(rem * 3 + pending))
+    }
+}
+
+impl EscapedChars<'_> {
+    fn pending_str(&self) -> &str {
+        self.pending.get(self.pos..).unwrap_or_default()
+    }
+}
+
+impl FusedIterator for EscapedChars<'_> {}
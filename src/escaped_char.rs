@@ -0,0 +1,167 @@
+use core::{
+    fmt::{Debug, Display},
+    ops::Deref,
+};
+
+use crate::{Mode, allowed};
+
+/// Single escaped character stored inline without allocation. It derefs to
+/// [`str`].
+///
+/// It is either the character itself or the escape sequence of the
+/// character.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct EscapedChar {
+    buf: [u8; Self::CAPACITY],
+    len: u8,
+}
+
+/// Escape single character in the same way as [`crate::escape_str`] does.
+///
+/// The escape character and characters that are not allowed with the given
+/// mode are escaped. Reserved names and disallowed trailing characters are
+/// not considered, because they depend on the whole name.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_char, Mode};
+///
+/// assert_eq!(&*escape_char('a', '_', Mode::ALL), "a");
+/// assert_eq!(&*escape_char('_', '_', Mode::ALL), "_5F");
+/// assert_eq!(&*escape_char(':', '_', Mode::WINDOWS), "_3A");
+/// assert_eq!(&*escape_char(':', '_', Mode::UNIX), ":");
+/// ```
+pub fn escape_char(c: char, esc: char, mode: Mode) -> EscapedChar {
+    if c == esc || !allowed(c, mode) {
+        EscapedChar::escaped(c, esc)
+    } else {
+        EscapedChar::raw(c)
+    }
+}
+
+/// Case of the hex digits in escape sequences. Uppercase is the default.
+///
+/// Decoding functions such as [`crate::unescape_str`] accept both cases.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum HexCase {
+    /// Uppercase hex digits (e.g. `_2F`).
+    #[default]
+    Upper,
+    /// Lowercase hex digits (e.g. `_2f`).
+    Lower,
+}
+
+impl EscapedChar {
+    /// Escape character (4 bytes), width marker and at most 6 hex digits.
+    const CAPACITY: usize = 11;
+
+    /// Create the escape sequence for the given character.
+    pub(crate) fn escaped(c: char, esc: char) -> Self {
+        Self::escaped_value(c as u32, esc)
+    }
+
+    /// Create the escape sequence for the given character with the given
+    /// case of the hex digits.
+    pub(crate) fn escaped_cased(c: char, esc: char, case: HexCase) -> Self {
+        Self::escaped_value_cased(c as u32, esc, case)
+    }
+
+    /// Create the escape sequence for the given value. This is used also for
+    /// values that are not valid characters (e.g. invalid UTF-8 bytes).
+    ///
+    /// Values below `0x100` are escaped with two hex digits, values below
+    /// `0x10000` with `u` and four hex digits and larger values with `U` and
+    /// six hex digits.
+    pub(crate) fn escaped_value(n: u32, esc: char) -> Self {
+        Self::escaped_value_cased(n, esc, HexCase::Upper)
+    }
+
+    /// Same as [`Self::escaped_value`], but with the given case of the hex
+    /// digits. The width markers `u` and `U` are not affected.
+    pub(crate) fn escaped_value_cased(
+        n: u32,
+        esc: char,
+        case: HexCase,
+    ) -> Self {
+        Self::escaped_value_wide(n, esc, case, 0)
+    }
+
+    /// Create the escape sequence for invalid unit (invalid UTF-8 byte or
+    /// unpaired surrogate). It always has at least `u` and four hex digits
+    /// (e.g. `_u00FF` for byte `0xFF`). Characters below `0x100` are escaped
+    /// with two hex digits and surrogates are not characters, so the escape
+    /// sequence is different from the escape sequences of all characters.
+    #[cfg(feature = "std")]
+    pub(crate) fn escaped_unit(n: u32, esc: char) -> Self {
+        Self::escaped_value_wide(n, esc, HexCase::Upper, 0x100)
+    }
+
+    /// Create the escape sequence for the given value. The form is chosen as
+    /// if the value was at least `min`.
+    fn escaped_value_wide(n: u32, esc: char, case: HexCase, min: u32) -> Self {
+        let digits_tab = match case {
+            HexCase::Upper => b"0123456789ABCDEF",
+            HexCase::Lower => b"0123456789abcdef",
+        };
+        let mut res = Self::raw(esc);
+        let digits = match n.max(min) {
+            0..0x100 => 2,
+            0x100..0x10000 => {
+                res.push(b'u');
+                4
+            }
+            _ => {
+                res.push(b'U');
+                6
+            }
+        };
+        for i in (0..digits).rev() {
+            res.push(digits_tab[(n >> (i * 4)) as usize & 0xF]);
+        }
+        res
+    }
+
+    fn push(&mut self, b: u8) {
+        self.buf[self.len as usize] = b;
+        self.len += 1;
+    }
+
+    /// Create the character without escaping.
+    pub(crate) fn raw(c: char) -> Self {
+        let mut buf = [0; Self::CAPACITY];
+        let len = c.encode_utf8(&mut buf).len() as u8;
+        Self { buf, len }
+    }
+
+    /// Get the escaped character as string.
+    pub fn as_str(&self) -> &str {
+        // The buffer contains only encoded chars and ascii digits.
+        core::str::from_utf8(&self.buf[..self.len as usize]).unwrap()
+    }
+}
+
+impl Deref for EscapedChar {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for EscapedChar {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for EscapedChar {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Debug for EscapedChar {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
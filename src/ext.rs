@@ -0,0 +1,44 @@
+use alloc::string::String;
+
+use crate::{DEFAULT_ESC, Mode, escape_str};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for str {}
+    impl Sealed for alloc::string::String {}
+}
+
+/// Extension trait for escaping strings. This trait is sealed and cannot be
+/// implemented outside of this crate.
+///
+/// # Example
+/// ```
+/// use filesan::{Mode, SanitizeExt};
+///
+/// assert_eq!("a/b_c".sanitize(Mode::UNIX), "a_2Fb_5Fc");
+/// assert_eq!("a/b_c".sanitize_with('~', Mode::UNIX), "a~2Fb_c");
+/// ```
+pub trait SanitizeExt: sealed::Sealed {
+    /// Escape the string using [`DEFAULT_ESC`] as the escape character. See
+    /// [`escape_str`] for more info.
+    fn sanitize(&self, mode: Mode) -> String {
+        self.sanitize_with(DEFAULT_ESC, mode)
+    }
+
+    /// Escape the string using the given escape character. See
+    /// [`escape_str`] for more info.
+    fn sanitize_with(&self, esc: char, mode: Mode) -> String;
+}
+
+impl SanitizeExt for str {
+    fn sanitize_with(&self, esc: char, mode: Mode) -> String {
+        escape_str(self, esc, mode)
+    }
+}
+
+impl SanitizeExt for String {
+    fn sanitize_with(&self, esc: char, mode: Mode) -> String {
+        escape_str(self, esc, mode)
+    }
+}
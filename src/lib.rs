@@ -32,31 +32,111 @@
 //! );
 //! ```
 //!
-//! You can use [`Mode::SYSTEM`] to get your current target system. See
+//! You can use [`Mode::SYSTEM`] to get your current target system (on
+//! targets other than unix and windows it is [`Mode::ALL`]). See
 //! documentation of [`escape_str`] and [`replace_escape`] for more info.
+//!
+//! Strings escaped with [`escape_str`] can be converted back to the original
+//! with [`unescape_str`].
+//!
+//! # Escape format
+//! Each escaped character is replaced with the escape character followed by
+//! the code point of the character in uppercase hex:
+//! - `0x00` - `0xFF`: two hex digits (e.g. `_2F` for `/`)
+//! - `0x100` - `0xFFFF`: `u` and four hex digits (e.g. `_u20AC` for `€`)
+//! - `0x10000` - `0x10FFFF`: `U` and six hex digits (e.g. `_U01F600` for
+//!   `😀`)
+//!
+//! The form of each sequence is given by its first character, so the
+//! sequences are never ambiguous and any character may be escaped. Decoders
+//! such as [`unescape_str`] also accept lowercase hex digits, but the markers
+//! `u` and `U` are case sensitive.
+//!
+//! # Features
+//! - `std` (default): functions working with `OsStr` and `Path`. Without
+//!   this feature, the crate is `no_std` and requires only `alloc`.
+//! - `translit`: ASCII transliteration with `transliterate` and
+//!   `escape_str_translit`.
+//! - `serde`: `Serialize` and `Deserialize` for `Mode`.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+use alloc::{borrow::Cow, string::String};
+use core::{fmt::Write, str::CharIndices};
+
+mod blocklist;
+mod bytes;
+#[cfg(feature = "std")]
+mod cache;
 mod char_flags;
+mod check;
+mod checked;
+mod concat;
+mod escaped;
+mod escaped_char;
+mod ext;
+mod lookalike;
+mod lossy;
+#[cfg(feature = "serde")]
+mod mode_serde;
+#[cfg(feature = "std")]
+mod os_str;
+mod path;
+mod plan;
+mod replacement;
+mod report;
+mod rules;
+mod sanitizer;
+mod slice;
+mod stem;
+mod stream;
+mod style;
+mod suggest;
+#[cfg(feature = "translit")]
+mod translit;
+mod truncate;
+mod unescape;
+mod valid_name;
+mod validate;
+mod why;
 
-pub use self::char_flags::*;
+#[cfg(feature = "std")]
+pub use self::cache::*;
+#[cfg(feature = "std")]
+pub use self::os_str::*;
+#[cfg(feature = "translit")]
+pub use self::translit::*;
+pub use self::{
+    blocklist::*, bytes::*, char_flags::*, check::*, checked::*, concat::*,
+    escaped::*, escaped_char::*, ext::*, lookalike::*, lossy::*, path::*,
+    plan::*, replacement::*, report::*, rules::*, sanitizer::*, slice::*,
+    stem::*, stream::*, style::*, suggest::*, truncate::*, unescape::*,
+    valid_name::*, validate::*, why::*,
+};
 
 const NON: Mode = Mode::NONE;
 const WWW: Mode = Mode::WINDOWS;
-const WWM: Mode =
-    Mode::from_bits_retain(Mode::WINDOWS.bits() | Mode::MAC.bits());
-const UWM: Mode = Mode::from_bits_retain(
-    Mode::UNIX.bits() | Mode::WINDOWS.bits() | Mode::MAC.bits(),
-);
+const WMH: Mode = Mode::WINDOWS_MAC.union(Mode::HFS);
+const UWM: Mode = Mode::UNIX_WINDOWS.union(Mode::MAC);
+const ALL: Mode = UWM.union(Mode::HFS);
 const WEE: Mode = Mode::WINDOWS_END;
 
+/// Modes that reserve the names `.` and `..`.
+const UNIX_LIKE: Mode = Mode::UNIX.union(Mode::MAC).union(Mode::POSIX);
+
 const DISALLOWED_CHARS: &[Mode] = &[
     // NUL SOH STX ETX  EOT  ENQ  ACK  BEL  BS   TAB  LF   VT   FF   CR   SO
-    UWM, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW,
+    ALL, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW,
     // SI DLE DC1  DC2  DC3  DC4  NAK  SYN  ETB  CAN  EM   SUB  ESC  FS   GS
-    UWM, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW,
+    WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW, WWW,
     // RS US  SP   !    "    #    $    %    &    '    (    )    *    +    ,
     WWW, WWW, WEE, NON, WWW, NON, NON, NON, NON, NON, NON, NON, WWW, NON, NON,
     // - .    /    0    1    2    3    4    5    6    7    8    9    :    ;
-    NON, WEE, UWM, NON, NON, NON, NON, NON, NON, NON, NON, NON, NON, WWM, NON,
+    NON, WEE, UWM, NON, NON, NON, NON, NON, NON, NON, NON, NON, NON, WMH, NON,
     // < =    >    ?    @    A    B    C    D    E    F    G    H    I    J
     WWW, NON, WWW, WWW, NON, NON, NON, NON, NON, NON, NON, NON, NON, NON, NON,
     // K L    M    N    O    P    Q    R    S    T    U    V    W    X    Y
@@ -69,18 +149,26 @@ const DISALLOWED_CHARS: &[Mode] = &[
     NON, NON, NON, NON, WWW, NON, NON, NON,
 ];
 
+/// The default escape character used by [`escape`] and [`escape_all`].
+pub const DEFAULT_ESC: char = '_';
+
 /// Reserved filenames on windows.
 pub const WINDOWS_RESERVED: &[&str] = &[
     "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5",
     "COM6", "COM7", "COM8", "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5",
-    "LPT6", "LPT7", "LPT8", "LPT9",
+    "LPT6", "LPT7", "LPT8", "LPT9", "CONIN$", "CONOUT$", "CLOCK$", "COM¹",
+    "COM²", "COM³", "LPT¹", "LPT²", "LPT³",
 ];
 
 /// Reserved filenames on unix.
 pub const UNIX_RESERVED: &[&str] = &[".", ".."];
 
+/// Reserved filenames on cloud storage services. The names are case
+/// insensitive.
+pub const CLOUD_RESERVED: &[&str] = &[".lock", "desktop.ini"];
+
 /// Reserved filenames on the current target system (unix).
-#[cfg(unix)]
+#[cfg(all(unix, not(target_os = "macos")))]
 pub const SYSTEM_RESERVED: &[&str] = UNIX_RESERVED;
 /// Reserved filenames on the current target system (windows).
 #[cfg(windows)]
@@ -93,21 +181,211 @@ pub const SYSTEM_RESERVED: &[&str] = UNIX_RESERVED;
 ///
 /// Disallowed characters by mode:
 /// - [`Mode::UNIX`]: `\x00`, `/`
-/// - [`Mode::WINDOWS`]: `0x00` - `0x31`, `<`, `>`, `:`, `"`, `/`, `\`,
+/// - [`Mode::WINDOWS`]: `0x00` - `0x1F`, `<`, `>`, `:`, `"`, `/`, `\`,
 ///   `|`, `?`, `*`
 /// - [`Mode::MAC`]: `\x00`, `/`, `:`
 /// - [`Mode::ALL`]: all of the above.
 /// - [`Mode::SYSTEM`]: flag of the current target system.
 /// - [`Mode::WINDOWS_END`]: ` `, `.`
-pub fn allowed(c: char, mode: Mode) -> bool {
+/// - [`Mode::POSIX`]: all characters except `A` - `Z`, `a` - `z`, `0` - `9`,
+///   `.`, `_` and `-`
+/// - [`Mode::ASCII_ONLY`]: all non ASCII characters (`0x80` and larger)
+/// - [`Mode::FAT`]: the same as [`Mode::WINDOWS`]
+/// - [`Mode::HFS`]: `\x00`, `:`
+/// - [`Mode::CLOUD`]: the same as [`Mode::WINDOWS`]
+///
+/// This is `const fn`, so it can be used to build tables at compile time.
+pub const fn allowed(c: char, mode: Mode) -> bool {
     let n = c as u32 as usize;
-    if n >= DISALLOWED_CHARS.len() {
+    let posix = mode.contains(Mode::POSIX)
+        && !matches!(c, 'A'..='Z' | 'a'..='z' | '0'..='9' | '.' | '_' | '-');
+    if posix || mode.contains(Mode::ASCII_ONLY) && !c.is_ascii() {
+        false
+    } else if n >= DISALLOWED_CHARS.len() {
         true
     } else {
         !DISALLOWED_CHARS[n].intersects(mode)
     }
 }
 
+/// Checks if all the characters in the given string are allowed in path on the
+/// given systems. See [`allowed`] for the disallowed characters.
+///
+/// This checks only the characters. Reserved names and disallowed trailing
+/// characters are not considered. Use [`needs_escape`] to check that.
+///
+/// # Example
+/// ```
+/// use filesan::{allowed_str, Mode};
+///
+/// assert!(allowed_str("hello.txt", Mode::ALL));
+/// assert!(!allowed_str("a:b", Mode::WINDOWS));
+/// assert!(allowed_str("a:b", Mode::UNIX));
+/// // Reserved names are not considered
+/// assert!(allowed_str("NUL.", Mode::WINDOWS));
+/// ```
+pub fn allowed_str(s: &str, mode: Mode) -> bool {
+    // Bytes of multibyte characters are never ASCII, so it is enough to
+    // check the bytes.
+    s.bytes().all(|b| allowed_u8(b, mode))
+}
+
+/// Checks if the given byte is allowed in path on the given systems.
+///
+/// ASCII bytes are checked in the same way as in [`allowed`]. Bytes with
+/// value `0x80` or more are part of multibyte characters (or invalid UTF-8),
+/// so they are allowed unless the mode disallows all non ASCII characters
+/// (e.g. [`Mode::POSIX`]).
+///
+/// This is useful for byte oriented callers (e.g. when working with raw
+/// filenames on unix) that don't want to decode UTF-8.
+///
+/// # Example
+/// ```
+/// use filesan::{allowed_u8, Mode};
+///
+/// assert!(allowed_u8(b'a', Mode::ALL));
+/// assert!(!allowed_u8(b'/', Mode::UNIX));
+/// assert!(allowed_u8(0xFF, Mode::ALL));
+/// assert!(!allowed_u8(0xFF, Mode::ASCII_ONLY));
+/// ```
+pub const fn allowed_u8(b: u8, mode: Mode) -> bool {
+    if b.is_ascii() {
+        allowed(b as char, mode)
+    } else {
+        non_ascii_allowed(mode)
+    }
+}
+
+/// Iterate over the characters that are not allowed on the given systems
+/// together with their byte offsets. See [`allowed`] for the disallowed
+/// characters.
+///
+/// As with [`allowed_str`], only the characters are considered. Reserved
+/// names, disallowed trailing characters and the escape character are not
+/// reported.
+///
+/// # Example
+/// ```
+/// use filesan::{invalid_chars, Mode};
+///
+/// let invalid: Vec<_> = invalid_chars("ž:a/b", Mode::WINDOWS).collect();
+/// assert_eq!(invalid, [(2, ':'), (4, '/')]);
+/// ```
+pub fn invalid_chars(
+    s: &str,
+    mode: Mode,
+) -> impl DoubleEndedIterator<Item = (usize, char)> + Clone {
+    s.char_indices().filter(move |(_, c)| !allowed(*c, mode))
+}
+
+/// Get the first character that is not allowed on the given systems together
+/// with its byte offset. This is the same as the first item of
+/// [`invalid_chars`].
+///
+/// # Example
+/// ```
+/// use filesan::{first_invalid, Mode};
+///
+/// assert_eq!(first_invalid("ž:a/b", Mode::WINDOWS), Some((2, ':')));
+/// assert_eq!(first_invalid("ž:a/b", Mode::UNIX), Some((4, '/')));
+/// assert_eq!(first_invalid("a_b", Mode::ALL), None);
+/// ```
+pub fn first_invalid(s: &str, mode: Mode) -> Option<(usize, char)> {
+    invalid_chars(s, mode).next()
+}
+
+/// Iterate over the ASCII characters (`0x00` - `0x7F`) that are not allowed
+/// on the given systems. See [`allowed`] for the disallowed characters.
+///
+/// Non ASCII characters are not included, they are all disallowed with
+/// [`Mode::POSIX`] and [`Mode::ASCII_ONLY`] and allowed otherwise.
+///
+/// # Example
+/// ```
+/// use filesan::{disallowed_chars, Mode};
+///
+/// let unix: String = disallowed_chars(Mode::UNIX).collect();
+/// assert_eq!(unix, "\0/");
+/// let mac: String = disallowed_chars(Mode::MAC).collect();
+/// assert_eq!(mac, "\0/:");
+/// ```
+pub fn disallowed_chars(
+    mode: Mode,
+) -> impl DoubleEndedIterator<Item = char> + Clone {
+    (0..0x80u8)
+        .map(char::from)
+        .filter(move |c| !allowed(*c, mode))
+}
+
+/// Checks if the given filename is reserved on the given systems.
+///
+/// Reserved filenames by mode:
+/// - [`Mode::UNIX`]: `.`, `..`
+/// - [`Mode::WINDOWS`]: `CON`, `PRN`, `AUX`, `NUL`, `COM1` - `COM9`,
+///   `LPT1` - `LPT9`, `CONIN$`, `CONOUT$`, `CLOCK$`, `COM¹` - `COM³` and
+///   `LPT¹` - `LPT³` (with superscript digits), both with and without
+///   extension (e.g. `CON.txt`). The names are case insensitive.
+/// - [`Mode::MAC`]: `.`, `..`
+/// - [`Mode::ALL`]: all of the above.
+/// - [`Mode::SYSTEM`]: flag of the current target system.
+/// - [`Mode::POSIX`]: `.`, `..` and names starting with `-`.
+/// - [`Mode::FAT`]: the same as [`Mode::WINDOWS`].
+/// - [`Mode::HFS`]: no reserved names.
+/// - [`Mode::CLOUD`]: the same as [`Mode::WINDOWS`], `.lock`, `desktop.ini`
+///   and names starting with space or with `~$`. The names are case
+///   insensitive.
+///
+/// # Example
+/// ```
+/// use filesan::{is_reserved, Mode};
+///
+/// assert!(is_reserved("..", Mode::UNIX));
+/// assert!(!is_reserved("..", Mode::WINDOWS));
+/// assert!(is_reserved("CON.txt", Mode::WINDOWS));
+/// assert!(!is_reserved("CON.txt", Mode::UNIX));
+/// ```
+pub fn is_reserved(name: &str, mode: Mode) -> bool {
+    reserved_len(name.as_bytes(), mode) != 0
+}
+
+/// Iterate over the names that are reserved on the given systems.
+///
+/// Windows reserved names are also reserved with any extension and they are
+/// matched case insensitive, see [`is_reserved`]. Names starting with `-`
+/// that are reserved by [`Mode::POSIX`] and names with the prefixes reserved
+/// by [`Mode::CLOUD`] can't be enumerated, so they are not included.
+///
+/// # Example
+/// ```
+/// use filesan::{reserved_names, Mode};
+///
+/// let names: Vec<_> = reserved_names(Mode::UNIX | Mode::MAC).collect();
+/// assert_eq!(names, [".", ".."]);
+/// assert_eq!(reserved_names(Mode::ALL).count(), 33);
+/// assert_eq!(reserved_names(Mode::NONE).count(), 0);
+/// ```
+pub fn reserved_names(
+    mode: Mode,
+) -> impl DoubleEndedIterator<Item = &'static str> + Clone {
+    let windows = if mode.contains(Mode::WINDOWS) {
+        WINDOWS_RESERVED
+    } else {
+        &[]
+    };
+    let unix = if mode.intersects(UNIX_LIKE) {
+        UNIX_RESERVED
+    } else {
+        &[]
+    };
+    let cloud = if mode.contains(Mode::CLOUD) {
+        CLOUD_RESERVED
+    } else {
+        &[]
+    };
+    windows.iter().chain(unix).chain(cloud).copied()
+}
+
 /// Escape the given string so that it may be used as valid path on the given
 /// systems.
 ///
@@ -116,6 +394,9 @@ pub fn allowed(c: char, mode: Mode) -> bool {
 ///
 /// The escape character may be any character that you are sure that is valid
 /// in filename on the target OS. Good choice is for example the character `_`.
+/// Use [`try_escape_str`] if you want to check that the escape character is
+/// valid. The escape character may also be non ASCII character (e.g. `€`),
+/// the result is still reversible with [`unescape_str`].
 ///
 /// `mode` may be any combination of the following flags that combine features
 /// of disallowed filename features that will be escaped:
@@ -123,10 +404,11 @@ pub fn allowed(c: char, mode: Mode) -> bool {
 ///     - disallowed characters: `\x00`, `/`
 ///     - disallowed filenames: `.`, `..`
 /// - [`Mode::WINDOWS`]:
-///     - disallowed characters `0x00` - `0x31`, `<`, `>`, `:`, `"`, `/`, `\`,
+///     - disallowed characters `0x00` - `0x1F`, `<`, `>`, `:`, `"`, `/`, `\`,
 ///       `|`, `?`, `*`
 ///     - disallowed filenames (both with and without extension): `CON`, `PRN`,
-///       `AUX`, `NUL`, `COM1` - `COM9`, `LPT1` - `LPT9`
+///       `AUX`, `NUL`, `COM1` - `COM9`, `LPT1` - `LPT9`, `CONIN$`,
+///       `CONOUT$`, `CLOCK$`, `COM¹` - `COM³`, `LPT¹` - `LPT³`
 ///     - disallowed characters at the end: ` `, `.`
 /// - [`Mode::MAC`]:
 ///     - disallowed characters: `\x00`, `/`, `:`
@@ -135,11 +417,25 @@ pub fn allowed(c: char, mode: Mode) -> bool {
 /// - [`Mode::SYSTEM`]: flag of the current target system.
 /// - [`Mode::WINDOWS_END`]:
 ///     - disallowed characters: ` `, `.`
+/// - [`Mode::POSIX`]:
+///     - disallowed characters: all except `A` - `Z`, `a` - `z`, `0` - `9`,
+///       `.`, `_` and `-`
+///     - disallowed filenames: `.`, `..` and names starting with `-`
+/// - [`Mode::ASCII_ONLY`]:
+///     - disallowed characters: all non ASCII characters
+/// - [`Mode::FAT`]: the same as [`Mode::WINDOWS`]
+/// - [`Mode::HFS`]:
+///     - disallowed characters: `\x00`, `:`
+/// - [`Mode::CLOUD`]: the same as [`Mode::WINDOWS`] and also:
+///     - disallowed filenames: `.lock`, `desktop.ini` (case insensitive)
+///       and names starting with ` ` or `~$`
 ///
 /// # Returns
 /// String with escaped invalid paths. Escape character and invalid characters
-/// are replaced with escape character followed by two digit hex value of the
-/// character. Invalid filenames are prefixed with the escape character.
+/// are replaced with escape character followed by uppercase hex value of the
+/// character: two digits for values below `0x100`, `u` and four digits for
+/// values below `0x10000` and `U` and six digits for larger values. The first
+/// character of reserved names is escaped in the same way.
 ///
 /// # Example
 /// ```
@@ -164,20 +460,461 @@ pub fn allowed(c: char, mode: Mode) -> bool {
 /// );
 /// ```
 pub fn escape_str(p: &str, esc: char, mode: Mode) -> String {
-    escape_str_inner(
-        p,
-        esc,
-        mode,
-        |res, chr| *res += &format!("{esc}{:02X}", chr as u32),
-        |res, s| {
-            res.push(esc);
-            let Some(c) = s.chars().next() else {
-                return;
-            };
-            *res += &format!("{:02X}", c as u32);
-            *res += &s[c.len_utf8()..];
-        },
-    )
+    let mut res = String::new();
+    escape_str_into(p, esc, mode, &mut res);
+    res
+}
+
+/// Escape the given string so that it may be used as valid path on the given
+/// systems. The result is appended to `out`.
+///
+/// This is the same as [`escape_str`], but it allows reusing the buffer when
+/// escaping many strings. `out` is not cleared.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_into, Mode};
+///
+/// let mut res = String::new();
+/// for (name, expected) in
+///     [("hello", "hello"), ("the_re", "the_5Fre"), ("NUL", "_4EUL")]
+/// {
+///     res.clear();
+///     escape_str_into(name, '_', Mode::WINDOWS, &mut res);
+///     assert_eq!(res, expected);
+/// }
+///
+/// let mut res = "dir/".to_string();
+/// escape_str_into("a/b", '_', Mode::UNIX, &mut res);
+/// assert_eq!(res, "dir/a_2Fb");
+/// ```
+pub fn escape_str_into(p: &str, esc: char, mode: Mode, out: &mut String) {
+    escape_str_with_into(p, esc, mode, HexCase::Upper, out, |_, _| {
+        Replacement::Hex
+    })
+}
+
+/// Escape the given string using custom predicate for allowed characters.
+///
+/// The escape character and all the characters for which `allowed` returns
+/// `false` are escaped in the same way as in [`escape_str`]. Reserved names
+/// and trailing characters are not handled, so the predicate can describe
+/// any filesystem. Unique inputs will also generate unique outputs.
+///
+/// # Example
+/// ```
+/// use filesan::escape_with;
+///
+/// assert_eq!(
+///     escape_with("a#b_c d", '_', |c| c != '#' && c != ' '),
+///     "a_23b_5Fc_20d"
+/// );
+/// ```
+pub fn escape_with<F: Fn(char) -> bool>(
+    p: &str,
+    esc: char,
+    allowed: F,
+) -> String {
+    let mut res = String::new();
+    let chars = p.chars().map(|c| (c, c == esc || !allowed(c)));
+    escape_str_inner(chars, &mut res, |res, chr| {
+        _ = write_escape(res, chr, esc);
+    });
+    res
+}
+
+/// Escape the given string, but use separate prefix character for reserved
+/// names.
+///
+/// This is the same as [`escape_str`], except that reserved names are not
+/// escaped by escaping their first character. Instead `prefix` is inserted
+/// before the name (e.g. `NUL` becomes `~NUL`). To keep the result unique,
+/// all occurrences of `prefix` in the string are escaped with `esc`.
+///
+/// `prefix` should be character allowed on the given systems and different
+/// from `esc`. The original string can then be retrieved by stripping the
+/// leading `prefix` and using [`unescape_str`].
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_prefixed, unescape_str, Mode};
+///
+/// assert_eq!(escape_str_prefixed("NUL", '_', '~', Mode::WINDOWS), "~NUL");
+/// assert_eq!(
+///     escape_str_prefixed("~NUL", '_', '~', Mode::WINDOWS),
+///     "_7ENUL"
+/// );
+/// assert_eq!(escape_str_prefixed("..", '_', '~', Mode::UNIX), "~..");
+/// assert_eq!(escape_str_prefixed(".", '_', '~', Mode::ALL), "~_2E");
+///
+/// let res = escape_str_prefixed("nul.a~b", '_', '~', Mode::WINDOWS);
+/// assert_eq!(res, "~nul.a_7Eb");
+/// let orig = unescape_str(res.strip_prefix('~').unwrap_or(&res), '_');
+/// assert_eq!(orig, "nul.a~b");
+/// ```
+pub fn escape_str_prefixed(
+    p: &str,
+    esc: char,
+    prefix: char,
+    mode: Mode,
+) -> String {
+    let mut res = String::new();
+    let mut cls = Classify::new(p, esc, mode);
+    while let Some((c, r)) = cls.next_reason() {
+        match r {
+            Some(Reason::Reserved) => {
+                res.push(prefix);
+                // The character may also need to be escaped for other
+                // reasons.
+                if c == esc
+                    || c == prefix
+                    || !allowed(c, mode)
+                    || trailing_start(p.as_bytes(), mode) == 0
+                {
+                    _ = write_escape(&mut res, c, esc);
+                } else {
+                    res.push(c);
+                }
+            }
+            Some(_) => _ = write_escape(&mut res, c, esc),
+            None if c == prefix => _ = write_escape(&mut res, c, esc),
+            None => res.push(c),
+        }
+    }
+    res
+}
+
+/// Escape the given string so that escaping it again doesn't change it.
+///
+/// This is the same as [`escape_str`], but escape characters that are
+/// followed by valid escape sequence in the same form as produced by
+/// [`escape_str`] or [`escape_str_cased`] (e.g. two hex digits of the same
+/// case) are kept as they are, because they are considered to already be
+/// escape sequences. As a result,
+/// `escape_str_idempotent(&escape_str_idempotent(s, ..), ..)` is the same as
+/// `escape_str_idempotent(s, ..)`.
+///
+/// This sacrifices uniqueness. Different strings may produce the same result
+/// (e.g. `a/` and `a_2F`), so the result cannot be reliably unescaped.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_idempotent, Mode};
+///
+/// let res = escape_str_idempotent("a_2F/b_c", '_', Mode::UNIX);
+/// assert_eq!(res, "a_2F_2Fb_5Fc");
+/// assert_eq!(escape_str_idempotent(&res, '_', Mode::UNIX), res);
+/// ```
+pub fn escape_str_idempotent(p: &str, esc: char, mode: Mode) -> String {
+    let mut res = String::new();
+    let mut cls = Classify::new(p, esc, mode);
+    while let Some((c, r)) = cls.next_reason() {
+        let escaped = r.is_some_and(|r| {
+            r != Reason::Escape || !starts_with_escape(cls.as_str(), esc)
+        });
+        if escaped {
+            _ = write_escape(&mut res, c, esc);
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
+
+/// Checks whether the string starts with escape sequence (without the escape
+/// character) in the same form as it is produced by [`escape_str`] or
+/// [`escape_str_cased`] with any case.
+fn starts_with_escape(s: &str, esc: char) -> bool {
+    decode_hex(s, 0).is_ok_and(|(c, len)| {
+        [HexCase::Upper, HexCase::Lower].into_iter().any(|case| {
+            EscapedChar::escaped_cased(c, esc, case)[esc.len_utf8()..]
+                == s[..len]
+        })
+    })
+}
+
+/// Escape the given string in the same way as [`escape_str`], but keep the
+/// escape character as it is.
+///
+/// **This breaks the uniqueness guarantee of [`escape_str`].** Different
+/// inputs may give the same result (e.g. `a_2F` and `a/`) and the result
+/// can't be reliably unescaped with [`unescape_str`]. Use this only to get
+/// readable names (e.g. for display), never for names that need to be
+/// converted back. The escape character is still escaped if it is the first
+/// character of reserved name.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_raw, Mode};
+///
+/// let res = escape_str_raw("my_file_name", '_', Mode::NONE);
+/// assert_eq!(res, "my_file_name");
+/// assert_eq!(escape_str_raw("a_b/c", '_', Mode::UNIX), "a_b_2Fc");
+/// ```
+pub fn escape_str_raw(p: &str, esc: char, mode: Mode) -> String {
+    escape_str_with(p, esc, mode, |_, r| match r {
+        Reason::Escape => Replacement::Keep,
+        _ => Replacement::Hex,
+    })
+}
+
+/// Escape the given string in the same way as [`escape_str`], but with the
+/// given case of the hex digits in escape sequences.
+///
+/// The width markers `u` and `U` are not affected by the case. Unique inputs
+/// still generate unique outputs and the result can be unescaped with
+/// [`unescape_str`], which accepts both cases.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_cased, unescape_str, HexCase, Mode};
+///
+/// let res = escape_str_cased("a/b:€", '_', Mode::ALL, HexCase::Lower);
+/// assert_eq!(res, "a_2fb_3a€");
+/// assert_eq!(unescape_str(&res, '_'), "a/b:€");
+/// assert_eq!(
+///     escape_str_cased("a/b:", '_', Mode::ALL, HexCase::Upper),
+///     "a_2Fb_3A"
+/// );
+/// ```
+pub fn escape_str_cased(
+    p: &str,
+    esc: char,
+    mode: Mode,
+    case: HexCase,
+) -> String {
+    let mut res = String::new();
+    escape_str_with_into(p, esc, mode, case, &mut res, |_, _| {
+        Replacement::Hex
+    });
+    res
+}
+
+/// Escape the given string with automatically chosen escape character.
+///
+/// The first character from `candidates` that is not present in the string
+/// is used as the escape character. If all of them are present, the first
+/// candidate is used. If `candidates` is empty, [`DEFAULT_ESC`] is used.
+/// This avoids escaping many characters of the string only because they are
+/// the same as the escape character.
+///
+/// Returns the escaped string and the chosen escape character. The escape
+/// character must be stored if the string is to be unescaped later.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_auto, unescape_str, Mode};
+///
+/// let (res, esc) = escape_str_auto("my_file:name", &['_', '~'], Mode::ALL);
+/// assert_eq!(res, "my_file~3Aname");
+/// assert_eq!(esc, '~');
+/// assert_eq!(unescape_str(&res, esc), "my_file:name");
+/// ```
+pub fn escape_str_auto(
+    p: &str,
+    candidates: &[char],
+    mode: Mode,
+) -> (String, char) {
+    let esc = candidates
+        .iter()
+        .find(|c| !p.contains(**c))
+        .or(candidates.first())
+        .copied()
+        .unwrap_or(DEFAULT_ESC);
+    (escape_str(p, esc, mode), esc)
+}
+
+/// Get the length in bytes of the string that would be produced by
+/// [`escape_str`].
+///
+/// This doesn't allocate, so it can be used to preallocate buffer for the
+/// escaped string.
+///
+/// # Example
+/// ```
+/// use filesan::{escaped_len, escape_str, Mode};
+///
+/// assert_eq!(escaped_len("hello.txt", '_', Mode::ALL), 9);
+/// assert_eq!(escaped_len("NUL.txt", '_', Mode::WINDOWS), 9);
+/// assert_eq!(
+///     escaped_len("a:b.", '_', Mode::WINDOWS),
+///     escape_str("a:b.", '_', Mode::WINDOWS).len(),
+/// );
+/// ```
+pub fn escaped_len(p: &str, esc: char, mode: Mode) -> usize {
+    Classify::new(p, esc, mode)
+        .map(|(c, e)| {
+            if e {
+                EscapedChar::escaped(c, esc).len()
+            } else {
+                c.len_utf8()
+            }
+        })
+        .sum()
+}
+
+/// Escape the given string so that it may be used as valid path on the
+/// current target system. Uses [`DEFAULT_ESC`] as the escape character.
+///
+/// This is the same as `escape_str(p, DEFAULT_ESC, Mode::SYSTEM)`. See
+/// [`escape_str`] for more info.
+///
+/// # Example
+/// ```
+/// use filesan::escape;
+///
+/// assert_eq!(escape("hello_there"), "hello_5Fthere");
+/// assert_eq!(escape("a/b"), "a_2Fb");
+/// ```
+pub fn escape(p: &str) -> String {
+    escape_str(p, DEFAULT_ESC, Mode::SYSTEM)
+}
+
+/// Escape the given string so that it may be used as valid path on all the
+/// supported systems. Uses [`DEFAULT_ESC`] as the escape character.
+///
+/// This is useful for files that will be shared between different systems.
+/// It is the same as `escape_str(p, DEFAULT_ESC, Mode::ALL)`. See
+/// [`escape_str`] for more info.
+///
+/// # Example
+/// ```
+/// use filesan::escape_all;
+///
+/// assert_eq!(escape_all("a/b:c."), "a_2Fb_3Ac_2E");
+/// assert_eq!(escape_all("NUL.txt"), "_4EUL.txt");
+/// ```
+pub fn escape_all(p: &str) -> String {
+    escape_portable(p, DEFAULT_ESC)
+}
+
+/// Escape the given string so that it is valid path on all the supported
+/// systems.
+///
+/// All the disallowed characters, reserved names and disallowed trailing
+/// characters of all the systems are escaped. This is the same as
+/// `escape_str(p, esc, Mode::ALL)`. See [`escape_str`] for more info.
+///
+/// # Example
+/// ```
+/// use filesan::escape_portable;
+///
+/// assert_eq!(escape_portable("aux ", '_'), "aux_20");
+/// assert_eq!(escape_portable("report.", '_'), "report_2E");
+/// assert_eq!(escape_portable("..", '_'), "_2E_2E");
+/// assert_eq!(escape_portable("a:b/c", '_'), "a_3Ab_2Fc");
+/// ```
+pub fn escape_portable(p: &str, esc: char) -> String {
+    escape_str(p, esc, Mode::ALL)
+}
+
+/// Escape the given string so that it may be used as valid path on the given
+/// systems. Allocate only if the string needs to be changed.
+///
+/// The result is always the same as the result of [`escape_str`], but if the
+/// string doesn't need to be escaped, it is borrowed instead of copied.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use filesan::{escape_str_cow, Mode};
+///
+/// assert!(matches!(
+///     escape_str_cow("hello.txt", '_', Mode::ALL),
+///     Cow::Borrowed("hello.txt")
+/// ));
+/// assert_eq!(escape_str_cow("hello.", '_', Mode::ALL), "hello_2E");
+/// ```
+#[doc(alias = "escape_cow")]
+pub fn escape_str_cow(p: &str, esc: char, mode: Mode) -> Cow<'_, str> {
+    if needs_escape(p, esc, mode) {
+        Cow::Owned(escape_str(p, esc, mode))
+    } else {
+        Cow::Borrowed(p)
+    }
+}
+
+/// Escape the given string in the same way as [`escape_str`], but return
+/// `fallback` if the string is empty. Empty name is not valid on any system.
+///
+/// `fallback` is returned as it is, so it should be valid name. The string
+/// that contains only the escape character is never produced by
+/// [`escape_str`], so using it as the fallback keeps the results unique.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_or, Mode};
+///
+/// assert_eq!(escape_str_or("", '_', Mode::ALL, "_"), "_");
+/// assert_eq!(escape_str_or("_", '_', Mode::ALL, "_"), "_5F");
+/// assert_eq!(escape_str_or("a/b", '_', Mode::ALL, "_"), "a_2Fb");
+/// ```
+pub fn escape_str_or(
+    p: &str,
+    esc: char,
+    mode: Mode,
+    fallback: &str,
+) -> String {
+    if p.is_empty() {
+        fallback.into()
+    } else {
+        escape_str(p, esc, mode)
+    }
+}
+
+/// Checks whether [`escape_str`] would change the given string.
+///
+/// This is faster than escaping the string and comparing the result because
+/// it doesn't allocate and it stops at the first character that would be
+/// escaped. The string needs to be escaped if it contains the escape character
+/// or disallowed character, if it is reserved name or if it ends with
+/// disallowed character.
+///
+/// # Example
+/// ```
+/// use filesan::{needs_escape, Mode};
+///
+/// assert!(!needs_escape("hello.txt", '_', Mode::ALL));
+/// assert!(needs_escape("hello_there.txt", '_', Mode::ALL));
+/// assert!(needs_escape("hello:there.txt", '_', Mode::WINDOWS));
+/// assert!(!needs_escape("hello:there.txt", '_', Mode::UNIX));
+/// assert!(needs_escape("NUL.txt", '_', Mode::WINDOWS));
+/// assert!(needs_escape("hello.", '_', Mode::WINDOWS));
+/// ```
+pub fn needs_escape(p: &str, esc: char, mode: Mode) -> bool {
+    Classify::new(p, esc, mode).any(|(_, e)| e)
+}
+
+/// Checks whether the given string may be result of [`escape_str`] with the
+/// same arguments.
+///
+/// This is true if every escape character starts valid escape sequence and
+/// escaping the unescaped string gives the same string with either case of
+/// hex digits (see [`escape_str_cased`]). So the string
+/// contains only allowed characters, it is not reserved name and only the
+/// characters that [`escape_str`] would escape are escaped.
+///
+/// This is only heuristic. The string `a_2Fb` may be escaped `a/b`, but it
+/// may also be literal name that was never escaped.
+///
+/// # Example
+/// ```
+/// use filesan::{is_escaped, Mode};
+///
+/// assert!(is_escaped("a_2Fb", '_', Mode::UNIX));
+/// assert!(is_escaped("hello.txt", '_', Mode::UNIX));
+/// assert!(!is_escaped("a_b", '_', Mode::UNIX));
+/// // `a` would not be escaped.
+/// assert!(!is_escaped("_61", '_', Mode::UNIX));
+/// assert!(!is_escaped("a/b", '_', Mode::UNIX));
+/// assert!(is_escaped("a_2fb", '_', Mode::UNIX));
+/// ```
+pub fn is_escaped(s: &str, esc: char, mode: Mode) -> bool {
+    try_unescape_str(s, esc).is_ok_and(|u| {
+        [HexCase::Upper, HexCase::Lower]
+            .into_iter()
+            .any(|case| escape_str_cased(&u, esc, mode, case) == s)
+    })
 }
 
 /// Escape the given string so that it may be used as valid path on the given
@@ -197,10 +934,11 @@ pub fn escape_str(p: &str, esc: char, mode: Mode) -> String {
 ///     - disallowed characters: `\x00`, `/`
 ///     - disallowed filenames: `.`, `..`
 /// - [`Mode::WINDOWS`]:
-///     - disallowed characters `0x00` - `0x31`, `<`, `>`, `:`, `"`, `/`, `\`,
+///     - disallowed characters `0x00` - `0x1F`, `<`, `>`, `:`, `"`, `/`, `\`,
 ///       `|`, `?`, `*`
 ///     - disallowed filenames (both with and without extension): `CON`, `PRN`,
-///       `AUX`, `NUL`, `COM1` - `COM9`, `LPT1` - `LPT9`
+///       `AUX`, `NUL`, `COM1` - `COM9`, `LPT1` - `LPT9`, `CONIN$`,
+///       `CONOUT$`, `CLOCK$`, `COM¹` - `COM³`, `LPT¹` - `LPT³`
 ///     - disallowed characters at the end: ` `, `.`
 /// - [`Mode::MAC`]:
 ///     - disallowed characters: `\x00`, `/`, `:`
@@ -209,6 +947,18 @@ pub fn escape_str(p: &str, esc: char, mode: Mode) -> String {
 /// - [`Mode::SYSTEM`]: flag of the current target system.
 /// - [`Mode::WINDOWS_END`]:
 ///     - disallowed characters: ` `, `.`
+/// - [`Mode::POSIX`]:
+///     - disallowed characters: all except `A` - `Z`, `a` - `z`, `0` - `9`,
+///       `.`, `_` and `-`
+///     - disallowed filenames: `.`, `..` and names starting with `-`
+/// - [`Mode::ASCII_ONLY`]:
+///     - disallowed characters: all non ASCII characters
+/// - [`Mode::FAT`]: the same as [`Mode::WINDOWS`]
+/// - [`Mode::HFS`]:
+///     - disallowed characters: `\x00`, `:`
+/// - [`Mode::CLOUD`]: the same as [`Mode::WINDOWS`] and also:
+///     - disallowed filenames: `.lock`, `desktop.ini` (case insensitive)
+///       and names starting with ` ` or `~$`
 ///
 /// # Returns
 /// String with escaped invalid paths. Escape character and invalid characters
@@ -238,86 +988,298 @@ pub fn escape_str(p: &str, esc: char, mode: Mode) -> String {
 /// );
 /// ```
 pub fn replace_escape(p: &str, esc: char, mode: Mode) -> String {
-    escape_str_inner(
-        p,
-        esc,
-        mode,
-        |res, _| res.push(esc),
-        |res, mut s| {
-            while let Some(post) = s.strip_prefix(esc) {
-                s = post;
-                res.push(esc);
-            }
-            if !s.is_empty() {
-                res.push(esc);
-                *res += &s[1..];
-            }
-        },
-    )
+    let mut res = String::new();
+    escape_str_inner(Classify::new(p, esc, mode), &mut res, |res, _| {
+        res.push(esc)
+    });
+    res
 }
 
+/// Push the characters to `res`. `chr` is used to push characters that
+/// should be escaped.
 fn escape_str_inner(
-    mut p: &str,
+    chars: impl IntoIterator<Item = (char, bool)>,
+    res: &mut String,
+    mut chr: impl FnMut(&mut String, char),
+) {
+    for (c, e) in chars {
+        if e {
+            chr(res, c);
+        } else {
+            res.push(c);
+        }
+    }
+}
+
+/// Write the escape sequence for the given character.
+fn write_escape(w: &mut impl Write, c: char, esc: char) -> core::fmt::Result {
+    w.write_str(&EscapedChar::escaped(c, esc))
+}
+
+/// Iterates over characters of a string and decides whether each of them
+/// should be escaped.
+#[derive(Clone)]
+struct Classify<'a> {
+    chars: CharIndices<'a>,
     esc: char,
     mode: Mode,
-    mut chr: impl FnMut(&mut String, char),
-    mut name: impl FnMut(&mut String, &str),
-) -> String {
-    let mut res = String::new();
+    /// Length of the reserved part of the name. The first character of
+    /// reserved name is escaped.
+    reserved: usize,
+    /// Index from which all characters are escaped because they are at the
+    /// end.
+    trailing: usize,
+    /// ASCII characters that are allowed regardless of the mode.
+    allow: u128,
+    /// ASCII characters that are disallowed regardless of the mode.
+    disallow: u128,
+}
 
-    if mode.contains(Mode::WINDOWS) {
-        if let Some((s, r)) = p.rsplit_once('.') {
-            if windows_reserved_contains(s) {
-                name(&mut res, s);
-                res.push('.');
-                p = r;
-            }
-        } else if windows_reserved_contains(p) {
-            name(&mut res, p);
-            return res;
+impl<'a> Classify<'a> {
+    fn new(p: &'a str, esc: char, mode: Mode) -> Self {
+        Self {
+            chars: p.char_indices(),
+            esc,
+            mode,
+            reserved: reserved_len(p.as_bytes(), mode),
+            trailing: trailing_start(p.as_bytes(), mode),
+            allow: 0,
+            disallow: 0,
         }
     }
 
-    if res.is_empty()
-        && mode.intersects(Mode::UNIX | Mode::MAC)
-        && UNIX_RESERVED.contains(&p)
-    {
-        name(&mut res, p);
-        p = "";
+    /// Override whether ASCII characters are allowed. See
+    /// [`allowed_overridden`].
+    fn with_overrides(mut self, allow: u128, disallow: u128) -> Self {
+        self.allow = allow;
+        self.disallow = disallow;
+        self
+    }
+}
+
+impl Classify<'_> {
+    /// Get the remaining part of the string.
+    fn as_str(&self) -> &str {
+        self.chars.as_str()
+    }
+
+    /// Get the next character and the reason why it should be escaped.
+    /// [`None`] means that the character shouldn't be escaped.
+    fn next_reason(&mut self) -> Option<(char, Option<Reason>)> {
+        let (i, c) = self.chars.next()?;
+        let pos = Position {
+            idx: i,
+            reserved: self.reserved,
+            trailing: self.trailing,
+        };
+        let allowed =
+            allowed_overridden(c, self.mode, self.allow, self.disallow);
+        Some((c, pos.reason(c == self.esc, allowed)))
     }
+}
+
+impl Iterator for Classify<'_> {
+    type Item = (char, bool);
 
-    for c in p.chars() {
-        if c == esc || !allowed(c, mode) {
-            chr(&mut res, c);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_reason().map(|(c, r)| (c, r.is_some()))
+    }
+}
+
+/// Checks whether the character is allowed with the given overrides of ASCII
+/// characters. Each bit of the masks corresponds to the character with the
+/// same code. The overrides win over the mode.
+fn allowed_overridden(
+    c: char,
+    mode: Mode,
+    allow: u128,
+    disallow: u128,
+) -> bool {
+    let bit = 1u128.checked_shl(c as u32).unwrap_or_default();
+    if disallow & bit != 0 {
+        false
+    } else {
+        allow & bit != 0 || allowed(c, mode)
+    }
+}
+
+/// Position of character within the name.
+struct Position {
+    /// Index of the character.
+    idx: usize,
+    /// Length of the reserved part of the name or 0.
+    reserved: usize,
+    /// Index from which all characters are at the end of the name.
+    trailing: usize,
+}
+
+impl Position {
+    /// Decide why the character at this position should be escaped.
+    fn reason(&self, is_esc: bool, allowed: bool) -> Option<Reason> {
+        if self.idx == 0 && self.reserved != 0 {
+            Some(Reason::Reserved)
+        } else if is_esc {
+            Some(Reason::Escape)
+        } else if !allowed {
+            Some(Reason::Disallowed)
+        } else if self.idx >= self.trailing {
+            Some(Reason::Trailing)
         } else {
-            res.push(c);
+            None
         }
     }
+}
 
-    if mode.intersects(Mode::WINDOWS) {
-        if let Some(c) = res.pop() {
-            if !allowed(c, Mode::WINDOWS_END) {
-                chr(&mut res, c);
-            } else {
-                res.push(c);
-            }
+/// Reason why character is escaped. See [`escape_str_with`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Reason {
+    /// The character is the first character of reserved name.
+    Reserved,
+    /// The character is the escape character.
+    Escape,
+    /// The character is disallowed.
+    Disallowed,
+    /// The character is disallowed at the end of the name.
+    Trailing,
+}
+
+/// Checks whether non ASCII characters are allowed with the given mode.
+const fn non_ascii_allowed(mode: Mode) -> bool {
+    !mode.intersects(Mode::POSIX.union(Mode::ASCII_ONLY))
+}
+
+/// Get the length of the reserved part of the name. Returns 0 if the name is
+/// not reserved.
+fn reserved_len(p: &[u8], mode: Mode) -> usize {
+    if mode.contains(Mode::POSIX) && p.first() == Some(&b'-') {
+        return 1;
+    }
+
+    if mode.contains(Mode::WINDOWS) {
+        let stem = p.split(|b| *b == b'.').next().unwrap_or_default();
+        if windows_reserved_contains(stem) {
+            return stem.len();
         }
     }
 
-    res
+    if mode.contains(Mode::CLOUD) {
+        if let Some(len) = cloud_reserved_prefix(p) {
+            return len;
+        }
+        if CLOUD_RESERVED
+            .iter()
+            .any(|n| n.as_bytes().eq_ignore_ascii_case(p))
+        {
+            return p.len();
+        }
+    }
+
+    if mode.intersects(UNIX_LIKE) && matches!(p, b"." | b"..") {
+        p.len()
+    } else {
+        0
+    }
 }
 
-fn windows_reserved_contains(s: &str) -> bool {
-    match s.len() {
-        3 => {
-            let s = s.to_ascii_uppercase();
-            matches!(s.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+/// Result of checking whether the start of a name is reserved.
+enum ReservedPrefix {
+    /// The name is not reserved regardless of the rest of the name.
+    No,
+    /// It depends on the rest of the name.
+    Maybe,
+    /// The name is reserved regardless of the rest of the name. Contains the
+    /// length of the reserved part.
+    Yes(usize),
+}
+
+/// Checks whether the given start of a name decides whether the name is
+/// reserved. This must be in sync with [`reserved_len`].
+fn reserved_prefix(head: &[u8], mode: Mode) -> ReservedPrefix {
+    if mode.contains(Mode::POSIX) && head.first() == Some(&b'-') {
+        return ReservedPrefix::Yes(1);
+    }
+
+    if mode.contains(Mode::WINDOWS) {
+        match head.iter().position(|b| *b == b'.') {
+            Some(i) if windows_reserved_contains(&head[..i]) => {
+                return ReservedPrefix::Yes(i);
+            }
+            None if windows_reserved_prefix(head) => {
+                return ReservedPrefix::Maybe;
+            }
+            _ => {}
         }
-        4 => {
-            let s = s.to_ascii_uppercase();
-            (s.starts_with("COM") || s.starts_with("LPT"))
-                && s[3..].chars().next().unwrap().is_ascii_digit()
+    }
+
+    let mut maybe = false;
+    if mode.contains(Mode::CLOUD) {
+        if let Some(len) = cloud_reserved_prefix(head) {
+            return ReservedPrefix::Yes(len);
         }
-        _ => false,
+        maybe = head == b"~"
+            || CLOUD_RESERVED.iter().any(|n| {
+                n.len() >= head.len()
+                    && n.as_bytes()[..head.len()].eq_ignore_ascii_case(head)
+            });
+    }
+
+    if maybe
+        || mode.intersects(UNIX_LIKE) && matches!(head, b"" | b"." | b"..")
+    {
+        ReservedPrefix::Maybe
+    } else {
+        ReservedPrefix::No
     }
 }
+
+/// Get the index from which all characters must be escaped because they are
+/// disallowed at the end of the name.
+fn trailing_start(p: &[u8], mode: Mode) -> usize {
+    if mode.intersects(Mode::WINDOWS) {
+        p.iter()
+            .rposition(|b| allowed(*b as char, Mode::WINDOWS_END))
+            .map_or(0, |i| i + 1)
+    } else {
+        p.len()
+    }
+}
+
+/// Get the length of the reserved prefix of name on cloud storage services.
+fn cloud_reserved_prefix(s: &[u8]) -> Option<usize> {
+    if s.starts_with(b" ") {
+        Some(1)
+    } else if s.starts_with(b"~$") {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Checks whether the string is start of windows reserved name.
+fn windows_reserved_prefix(s: &[u8]) -> bool {
+    const BASES: &[&[u8]] = &[
+        b"CONIN$", b"CONOUT$", b"CLOCK$", b"PRN", b"AUX", b"NUL", b"COM",
+        b"LPT",
+    ];
+    windows_reserved_contains(s)
+        || BASES.iter().any(|b| {
+            b.len() >= s.len() && b[..s.len()].eq_ignore_ascii_case(s)
+        })
+}
+
+fn windows_reserved_contains(s: &[u8]) -> bool {
+    const NAMES: &[&[u8]] = &[
+        b"CON", b"PRN", b"AUX", b"NUL", b"CONIN$", b"CONOUT$", b"CLOCK$",
+    ];
+    if NAMES.iter().any(|b| b.eq_ignore_ascii_case(s)) {
+        return true;
+    }
+    if s.len() < 4 {
+        return false;
+    }
+
+    let (base, num) = s.split_at(3);
+    (base.eq_ignore_ascii_case(b"COM") || base.eq_ignore_ascii_case(b"LPT"))
+        // Digits and superscript digits `¹`, `²` and `³`.
+        && matches!(num, [b'0'..=b'9'] | [0xC2, 0xB9 | 0xB2 | 0xB3])
+}
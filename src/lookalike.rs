@@ -0,0 +1,70 @@
+use alloc::string::String;
+
+use crate::{Mode, Reason, Replacement, allowed, escape_str_with};
+
+/// Visually similar replacements of characters that are disallowed on
+/// [`Mode::UNIX`], [`Mode::WINDOWS`] or [`Mode::MAC`]. Control characters
+/// have no printable lookalike, so they are not in the table.
+///
+/// This is the default table for [`escape_str_lookalike`]. It may be
+/// extended by creating new table that contains these entries.
+pub const LOOKALIKES: &[(char, char)] = &[
+    ('"', '\u{FF02}'),  // FULLWIDTH QUOTATION MARK
+    ('*', '\u{FF0A}'),  // FULLWIDTH ASTERISK
+    ('/', '\u{2215}'),  // DIVISION SLASH
+    (':', '\u{A789}'),  // MODIFIER LETTER COLON
+    ('<', '\u{FF1C}'),  // FULLWIDTH LESS-THAN SIGN
+    ('>', '\u{FF1E}'),  // FULLWIDTH GREATER-THAN SIGN
+    ('?', '\u{FF1F}'),  // FULLWIDTH QUESTION MARK
+    ('\\', '\u{29F5}'), // REVERSE SOLIDUS OPERATOR
+    ('|', '\u{FF5C}'),  // FULLWIDTH VERTICAL LINE
+];
+
+/// Escape the given string in the same way as [`crate::escape_str`], but
+/// replace disallowed characters with their lookalikes from `table` (e.g.
+/// [`LOOKALIKES`]).
+///
+/// Characters that have no lookalike in the table or whose lookalike is not
+/// allowed by the mode (e.g. all non ASCII characters with [`Mode::POSIX`])
+/// are escaped with hex escape sequences. The escape character, the first
+/// character of reserved names and characters disallowed at the end of the
+/// name are also always escaped with hex escape sequence.
+///
+/// Lookalikes in custom tables should be allowed and they shouldn't be
+/// disallowed at the end of the name, otherwise the result may not be valid.
+///
+/// The result is easier to read, but different names may give the same
+/// result (e.g. `a/b` and `a∕b`) and the lookalikes are not reversed by
+/// [`crate::unescape_str`].
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_lookalike, LOOKALIKES, Mode};
+///
+/// assert_eq!(
+///     escape_str_lookalike("a/b:c?\x01", '_', Mode::ALL, LOOKALIKES),
+///     "a∕b꞉c？_01"
+/// );
+/// assert_eq!(
+///     escape_str_lookalike("a/b", '_', Mode::POSIX, LOOKALIKES),
+///     "a_2Fb"
+/// );
+/// ```
+pub fn escape_str_lookalike(
+    p: &str,
+    esc: char,
+    mode: Mode,
+    table: &[(char, char)],
+) -> String {
+    escape_str_with(p, esc, mode, |c, r| {
+        let Reason::Disallowed = r else {
+            return Replacement::Hex;
+        };
+        match table.iter().find(|(d, _)| *d == c) {
+            Some((_, l)) if *l != esc && allowed(*l, mode) => {
+                Replacement::Str(String::from(*l).into())
+            }
+            _ => Replacement::Hex,
+        }
+    })
+}
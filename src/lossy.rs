@@ -0,0 +1,109 @@
+use alloc::string::String;
+
+use crate::{Classify, Mode, Reason, allowed};
+
+/// Replace all the characters that would be escaped by [`crate::escape_str`]
+/// with `rep`. If `collapse` is `true`, consecutive replacements are
+/// replaced with single `rep`.
+///
+/// The same characters as in [`crate::escape_str`] are replaced: disallowed
+/// characters, characters disallowed at the end of the name and the first
+/// character of reserved names. `rep` itself is kept as it is if it is
+/// allowed. The result is valid name for the given mode as long as `rep` is
+/// allowed and it isn't disallowed at the end of the name.
+///
+/// This is lossy and not injective: different names may give the same result
+/// (e.g. `a:b` and `a?b` both become `a_b` with [`Mode::WINDOWS`]) and the
+/// result can't be unescaped. Use [`crate::escape_str`] if the original name
+/// must be recoverable or if different names must stay different.
+///
+/// # Example
+/// ```
+/// use filesan::{replace_lossy, Mode};
+///
+/// assert_eq!(
+///     replace_lossy("foo:bar?.txt", '_', Mode::WINDOWS, false),
+///     "foo_bar_.txt"
+/// );
+/// assert_eq!(replace_lossy("a:?/b. .", '_', Mode::WINDOWS, true), "a_b_");
+/// assert_eq!(replace_lossy("NUL.txt", '_', Mode::WINDOWS, true), "_UL.txt");
+/// ```
+pub fn replace_lossy(
+    p: &str,
+    rep: char,
+    mode: Mode,
+    collapse: bool,
+) -> String {
+    let mut cls = Classify::new(p, rep, mode);
+    let mut res = String::new();
+    let mut replaced = false;
+    while let Some((c, r)) = cls.next_reason() {
+        match r {
+            None => res.push(c),
+            Some(Reason::Escape) if allowed(c, mode) => res.push(c),
+            Some(_) if collapse && replaced => continue,
+            Some(_) => {
+                res.push(rep);
+                replaced = true;
+                continue;
+            }
+        }
+        replaced = false;
+    }
+    res
+}
+
+/// Replace disallowed characters with `replacement` and collapse
+/// consecutive replacements. This is the same as [`replace_lossy`] with
+/// `collapse` set to `true`, see it for more details, except that empty name
+/// becomes single `replacement`, so the result is never empty.
+///
+/// This is lossy, different names may give the same result.
+///
+/// # Example
+/// ```
+/// use filesan::{sanitize_replace, Mode};
+///
+/// let res = sanitize_replace("my/file.txt", '_', Mode::UNIX);
+/// assert_eq!(res, "my_file.txt");
+/// assert_eq!(sanitize_replace("a<>b. .", '_', Mode::WINDOWS), "a_b_");
+/// ```
+pub fn sanitize_replace(p: &str, replacement: char, mode: Mode) -> String {
+    if p.is_empty() {
+        String::from(replacement)
+    } else {
+        replace_lossy(p, replacement, mode, true)
+    }
+}
+
+/// Same as [`sanitize_replace`], but the replacements at the start and at the
+/// end of the name are removed (e.g. `/a//b?` becomes `a_b` with `_`). If
+/// nothing is left, the result is single `replacement`.
+///
+/// The first character of reserved name is still replaced, so the result is
+/// valid name as long as `replacement` is allowed (see [`replace_lossy`]).
+///
+/// # Example
+/// ```
+/// use filesan::{sanitize_replace_collapsed, Mode};
+///
+/// let san = |s| sanitize_replace_collapsed(s, '_', Mode::WINDOWS);
+/// assert_eq!(san("a///b"), "a_b");
+/// assert_eq!(san("<a:b>. "), "a_b");
+/// assert_eq!(san("???"), "_");
+/// assert_eq!(san(":NUL"), "_UL");
+/// ```
+pub fn sanitize_replace_collapsed(
+    p: &str,
+    replacement: char,
+    mode: Mode,
+) -> String {
+    let windows = mode.intersects(Mode::WINDOWS);
+    let p = p
+        .trim_start_matches(|c| !allowed(c, mode))
+        .trim_end_matches(|c| {
+            !allowed(c, mode) || windows && !allowed(c, Mode::WINDOWS_END)
+        });
+
+    sanitize_replace(p, replacement, mode)
+}
@@ -0,0 +1,74 @@
+use alloc::string::String;
+use core::fmt;
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{Error, SeqAccess, Visitor},
+};
+
+use crate::Mode;
+
+/// Serialize as the string given by [`Display`](core::fmt::Display) (e.g.
+/// `"unix+windows"`) in human readable formats and as `u32` with the bits of
+/// the mode in binary formats.
+impl Serialize for Mode {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        if s.is_human_readable() {
+            s.collect_str(self)
+        } else {
+            s.serialize_u32(self.bits())
+        }
+    }
+}
+
+/// Deserialize from string in the format of [`FromStr`](core::str::FromStr)
+/// (e.g. `"unix+windows"`), from list of such strings (e.g. `["unix",
+/// "windows"]`) or from integer with the bits of the mode in human readable
+/// formats. Binary formats don't describe their types, so there it is
+/// deserialized only from `u32` with the bits of the mode.
+impl<'de> Deserialize<'de> for Mode {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        if d.is_human_readable() {
+            d.deserialize_any(ModeVisitor)
+        } else {
+            d.deserialize_u32(ModeVisitor)
+        }
+    }
+}
+
+struct ModeVisitor;
+
+impl<'de> Visitor<'de> for ModeVisitor {
+    type Value = Mode;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("mode names, list of mode names or mode bits")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        u32::try_from(v)
+            .map(Mode::from_bits_retain)
+            .map_err(|_| E::custom("mode bits out of range"))
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        u64::try_from(v)
+            .map_err(|_| E::custom("mode bits out of range"))
+            .and_then(|v| self.visit_u64(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<Self::Value, A::Error> {
+        let mut res = Mode::NONE;
+        while let Some(name) = seq.next_element::<String>()? {
+            res |= name.parse::<Mode>().map_err(A::Error::custom)?;
+        }
+        Ok(res)
+    }
+}
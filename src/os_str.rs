@@ -0,0 +1,90 @@
+use alloc::{string::String, vec::Vec};
+use std::ffi::{OsStr, OsString};
+
+use crate::{
+    EscapedChar, Mode, Position, allowed, reserved_len, trailing_start,
+};
+
+/// Escape the given OS string so that it may be used as valid path on the
+/// given systems.
+///
+/// This is the same as [`crate::escape_str`], but it also works with strings
+/// that are not valid unicode. On unix, invalid UTF-8 bytes are escaped. On
+/// windows, unpaired surrogates are escaped. Invalid units are escaped with
+/// the escape character followed by `u` and four uppercase hex digits of the
+/// unit (e.g. `_u00FF` for byte `0xFF` or `_uD800` for surrogate `0xD800`),
+/// so the result is always valid unicode. Characters never produce this
+/// form for values below `0x100` (`ÿ` becomes `_FF`), so unique inputs still
+/// generate unique outputs.
+///
+/// If the input is valid unicode, the result is the same as the result of
+/// [`crate::escape_str`].
+///
+/// # Example
+/// ```
+/// use std::ffi::OsStr;
+/// use filesan::{escape_os_str, Mode};
+///
+/// assert_eq!(
+///     escape_os_str(OsStr::new("the/file."), '_', Mode::WINDOWS),
+///     "the_2Ffile_2E",
+/// );
+/// ```
+pub fn escape_os_str(p: &OsStr, esc: char, mode: Mode) -> OsString {
+    let units: Vec<_> = units(p).collect();
+    // Invalid units are replaced with `U+FFFD` so that the positions of the
+    // characters can be determined. The replacement character is never part
+    // of reserved name or disallowed trailing characters.
+    let lossy: String = units
+        .iter()
+        .map(|u| u.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect();
+    let reserved = reserved_len(lossy.as_bytes(), mode);
+    let trailing = trailing_start(lossy.as_bytes(), mode);
+    let mut res = String::with_capacity(lossy.len());
+
+    for ((idx, _), u) in lossy.char_indices().zip(units) {
+        let c = match u {
+            Ok(c) => c,
+            Err(n) => {
+                res += &EscapedChar::escaped_unit(n, esc);
+                continue;
+            }
+        };
+        let pos = Position {
+            idx,
+            reserved,
+            trailing,
+        };
+        if pos.reason(c == esc, allowed(c, mode)).is_some() {
+            res += &EscapedChar::escaped(c, esc);
+        } else {
+            res.push(c);
+        }
+    }
+
+    res.into()
+}
+
+/// Iterate over the characters of the OS string. Invalid units are returned
+/// as error with their value.
+#[cfg(windows)]
+fn units(p: &OsStr) -> impl Iterator<Item = Result<char, u32>> {
+    use std::os::windows::ffi::OsStrExt;
+
+    char::decode_utf16(p.encode_wide())
+        .map(|r| r.map_err(|e| e.unpaired_surrogate() as u32))
+}
+
+/// Iterate over the characters of the OS string. Invalid units are returned
+/// as error with their value.
+#[cfg(not(windows))]
+fn units(p: &OsStr) -> impl Iterator<Item = Result<char, u32>> {
+    // On unix, this is the same as `OsStrExt::as_bytes`.
+    p.as_encoded_bytes().utf8_chunks().flat_map(|c| {
+        c.valid()
+            .chars()
+            .map(Ok)
+            .chain(c.invalid().iter().map(|b| Err(*b as u32)))
+    })
+}
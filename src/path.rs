@@ -0,0 +1,102 @@
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(feature = "std")]
+use crate::escape_os_str;
+use crate::{Classify, Mode, escape_str_inner, escape_str_into, write_escape};
+
+/// Escape each component of the given relative path so that it may be used
+/// as valid path on the given systems.
+///
+/// The path is split on `/` and also on `\` if `mode` contains
+/// [`Mode::WINDOWS`]. Each component is escaped with [`crate::escape_str`]
+/// and the components are joined with the original separators. Empty
+/// components and leading or trailing separators are preserved.
+///
+/// Note that components `.` and `..` are reserved on unix so they are also
+/// escaped.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_path, Mode};
+///
+/// assert_eq!(escape_path("a/b:c/d", '_', Mode::WINDOWS), "a/b_3Ac/d");
+/// assert_eq!(escape_path("a\\NUL/", '_', Mode::WINDOWS), "a\\_4EUL/");
+/// assert_eq!(escape_path("a\\b/../c", '_', Mode::UNIX), "a\\b/_2E./c");
+/// ```
+pub fn escape_path(p: &str, esc: char, mode: Mode) -> String {
+    let windows = mode.contains(Mode::WINDOWS);
+    let mut res = String::with_capacity(p.len());
+    let mut rest = p;
+    while let Some(i) = rest.find(|c| c == '/' || (windows && c == '\\')) {
+        escape_str_into(&rest[..i], esc, mode, &mut res);
+        // Separators are single byte.
+        res += &rest[i..i + 1];
+        rest = &rest[i + 1..];
+    }
+    escape_str_into(rest, esc, mode, &mut res);
+    res
+}
+
+/// Escape each normal component of the given path so that it may be used as
+/// valid path on the given systems.
+///
+/// Each [`Component::Normal`] is escaped with [`crate::escape_os_str`].
+/// Prefix, root directory, current directory (`.`) and parent directory
+/// (`..`) components are kept as they are, because they are not names. Use
+/// [`sanitize_component`] if the name must not refer to other directories.
+///
+/// The path is normalized in the same way as by [`Path::components`], so
+/// repeated separators and `.` in the middle of the path are removed.
+///
+/// # Example
+/// ```
+/// use std::path::Path;
+/// use filesan::{sanitize_path, Mode};
+///
+/// assert_eq!(
+///     sanitize_path(Path::new("/a/../b:c/NUL."), '_', Mode::WINDOWS),
+///     Path::new("/a/../b_3Ac/_4EUL_2E"),
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn sanitize_path(p: &Path, esc: char, mode: Mode) -> PathBuf {
+    let mut res = PathBuf::new();
+    for c in p.components() {
+        match c {
+            Component::Normal(n) => res.push(escape_os_str(n, esc, mode)),
+            _ => res.push(c),
+        }
+    }
+    res
+}
+
+/// Escape the given name so that it is single path component that may be
+/// used on the given systems.
+///
+/// This is the same as [`crate::escape_str`], but the path separators `/` and
+/// `\` are escaped with any mode and the names `.` and `..` are always
+/// escaped. The result never contains separator and it is never `.` or `..`,
+/// so it cannot refer to other directories. This is useful for example when
+/// extracting archives (zip slip).
+///
+/// Empty name stays empty.
+///
+/// # Example
+/// ```
+/// use filesan::{sanitize_component, Mode};
+///
+/// assert_eq!(sanitize_component("../a\\b", '_', Mode::NONE), ".._2Fa_5Cb");
+/// assert_eq!(sanitize_component("..", '_', Mode::WINDOWS), "_2E_2E");
+/// assert_eq!(sanitize_component(".", '_', Mode::NONE), "_2E");
+/// ```
+pub fn sanitize_component(name: &str, esc: char, mode: Mode) -> String {
+    let mut res = String::with_capacity(name.len());
+    let chars = Classify::new(name, esc, mode | Mode::UNIX)
+        .map(|(c, e)| (c, e || c == '\\'));
+    escape_str_inner(chars, &mut res, |res, c| {
+        _ = write_escape(res, c, esc);
+    });
+    res
+}
@@ -0,0 +1,90 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{Classify, EscapedChar, Mode};
+
+/// Recorded escapes of a string created with [`plan_escape`]. It can be
+/// applied to the string later with [`Plan::apply`] without classifying the
+/// string again.
+///
+/// The plan contains only plain offsets and replacement strings, so it can be
+/// easily stored or sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    /// Replacements in the string. They are sorted by their position and they
+    /// don't overlap.
+    pub edits: Vec<Edit>,
+}
+
+/// Single replacement in [`Plan`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Edit {
+    /// Byte offset of the start of the replaced part.
+    pub start: usize,
+    /// Byte offset of the end of the replaced part (exclusive).
+    pub end: usize,
+    /// The string that will replace the part.
+    pub replacement: String,
+}
+
+/// Decide how to escape the given string without escaping it. Applying the
+/// plan to the string gives the same result as [`crate::escape_str`] with
+/// the same arguments.
+///
+/// # Example
+/// ```
+/// use filesan::{plan_escape, Edit, Mode};
+///
+/// let name = "a:b.";
+/// let plan = plan_escape(name, '_', Mode::WINDOWS);
+/// assert_eq!(plan.edits[0], Edit {
+///     start: 1,
+///     end: 2,
+///     replacement: "_3A".to_string(),
+/// });
+/// assert_eq!(plan.apply(name), "a_3Ab_2E");
+/// ```
+pub fn plan_escape(s: &str, esc: char, mode: Mode) -> Plan {
+    let mut edits = Vec::new();
+    let mut cls = Classify::new(s, esc, mode);
+    loop {
+        let start = s.len() - cls.as_str().len();
+        let Some((c, e)) = cls.next() else {
+            break;
+        };
+        if e {
+            edits.push(Edit {
+                start,
+                end: start + c.len_utf8(),
+                replacement: EscapedChar::escaped(c, esc).to_string(),
+            });
+        }
+    }
+    Plan { edits }
+}
+
+impl Plan {
+    /// Check if the plan doesn't change the string.
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Apply the replacements to the given string. The string should be the
+    /// same string that was used to create the plan.
+    ///
+    /// # Panics
+    /// If the edits are out of bounds of the string or they are not on char
+    /// boundaries.
+    pub fn apply(&self, s: &str) -> String {
+        let mut res = String::with_capacity(s.len());
+        let mut pos = 0;
+        for e in &self.edits {
+            res += &s[pos..e.start];
+            res += &e.replacement;
+            pos = e.end;
+        }
+        res + &s[pos..]
+    }
+}
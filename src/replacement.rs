@@ -0,0 +1,86 @@
+use alloc::{borrow::Cow, string::String};
+
+use crate::{Classify, EscapedChar, HexCase, Mode, Reason};
+
+/// Replacement of character chosen by the closure in [`escape_str_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Replacement<'a> {
+    /// Keep the character as it is, even though it should be escaped.
+    Keep,
+    /// Remove the character from the result.
+    Remove,
+    /// Replace the character with the given string.
+    Str(Cow<'a, str>),
+    /// Escape the character in the same way as [`crate::escape_str`].
+    Hex,
+}
+
+/// Escape the given string in the same way as [`crate::escape_str`], but let
+/// `f` decide how each character that should be escaped is replaced.
+///
+/// `f` is called only for characters that [`crate::escape_str`] would
+/// escape, together with the reason why the character should be escaped.
+/// Returning [`Replacement::Hex`] for all characters gives the same result
+/// as [`crate::escape_str`].
+///
+/// Custom replacements don't preserve the uniqueness guarantee of
+/// [`crate::escape_str`], different inputs may give the same result and the
+/// result can't be unescaped with [`crate::unescape_str`]. It is also up to
+/// `f` to make sure that the replacements are valid (e.g. that the
+/// replacement of reserved name or trailing character doesn't create another
+/// reserved name or trailing character).
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_with, Mode, Replacement};
+///
+/// let res = escape_str_with("a:b/c\x01", '_', Mode::ALL, |c, _| match c {
+///     ':' => Replacement::Str("∶".into()),
+///     '/' => Replacement::Str("⁄".into()),
+///     _ => Replacement::Hex,
+/// });
+/// assert_eq!(res, "a∶b⁄c_01");
+/// ```
+pub fn escape_str_with<'a>(
+    p: &str,
+    esc: char,
+    mode: Mode,
+    f: impl FnMut(char, Reason) -> Replacement<'a>,
+) -> String {
+    let mut res = String::new();
+    escape_str_with_into(p, esc, mode, HexCase::Upper, &mut res, f);
+    res
+}
+
+/// Same as [`escape_str_with`], but the result is appended to `out` and the
+/// hex case may be chosen.
+pub(crate) fn escape_str_with_into<'a>(
+    p: &str,
+    esc: char,
+    mode: Mode,
+    case: HexCase,
+    out: &mut String,
+    f: impl FnMut(char, Reason) -> Replacement<'a>,
+) {
+    escape_classified_into(Classify::new(p, esc, mode), esc, case, out, f);
+}
+
+/// Escape the characters classified by `cls` into `out`.
+pub(crate) fn escape_classified_into<'a>(
+    mut cls: Classify,
+    esc: char,
+    case: HexCase,
+    out: &mut String,
+    mut f: impl FnMut(char, Reason) -> Replacement<'a>,
+) {
+    while let Some((c, r)) = cls.next_reason() {
+        match r.map_or(Replacement::Keep, |r| f(c, r)) {
+            Replacement::Keep => out.push(c),
+            Replacement::Remove => {}
+            Replacement::Str(s) => out.push_str(&s),
+            Replacement::Hex => {
+                out.push_str(&EscapedChar::escaped_cased(c, esc, case))
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use alloc::string::String;
+
+use crate::{
+    HexCase, Mode, Reason, Replacement, escape_str_with_into, trailing_start,
+};
+
+/// Result of [`escape_str_report`]. Contains the escaped name and information
+/// about what was changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EscapeOutcome {
+    /// The escaped name. It is the same as the result of
+    /// [`crate::escape_str`].
+    pub name: String,
+    /// `true` if the name is different from the original.
+    pub changed: bool,
+    /// Number of escaped characters. This includes characters escaped
+    /// because of reserved name or because they are at the end.
+    pub replaced: usize,
+    /// `true` if the name was reserved.
+    pub reserved_prefixed: bool,
+    /// `true` if characters at the end of the name were escaped because they
+    /// are not allowed at the end of name. This is `true` even if the
+    /// characters are also escaped for other reason (e.g. the escape
+    /// character or `.` of `..`).
+    pub trailing_fixed: bool,
+}
+
+/// Escape the given string in the same way as [`crate::escape_str`] and
+/// return information about what was changed.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_report, Mode};
+///
+/// let res = escape_str_report("NUL.a:.", '_', Mode::WINDOWS);
+/// assert_eq!(res.name, "_4EUL.a_3A_2E");
+/// assert!(res.changed);
+/// assert_eq!(res.replaced, 3);
+/// assert!(res.reserved_prefixed);
+/// assert!(res.trailing_fixed);
+/// ```
+pub fn escape_str_report(p: &str, esc: char, mode: Mode) -> EscapeOutcome {
+    let mut res = EscapeOutcome::default();
+    escape_str_with_into(
+        p,
+        esc,
+        mode,
+        HexCase::Upper,
+        &mut res.name,
+        |_, r| {
+            res.replaced += 1;
+            res.reserved_prefixed |= r == Reason::Reserved;
+            Replacement::Hex
+        },
+    );
+    res.changed = res.replaced != 0;
+    res.trailing_fixed = trailing_start(p.as_bytes(), mode) != p.len();
+    res
+}
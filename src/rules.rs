@@ -0,0 +1,175 @@
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use crate::{
+    CLOUD_RESERVED, Mode, UNIX_LIKE, UNIX_RESERVED, WINDOWS_RESERVED, allowed,
+    non_ascii_allowed,
+};
+
+/// How [`ReservedName`] is matched against names.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NameMatch {
+    /// The whole name must be the same (e.g. `..`).
+    Exact,
+    /// The whole name must be the same, ignoring ASCII case (e.g.
+    /// `desktop.ini`).
+    IgnoreCase,
+    /// The name must be the same ignoring ASCII case, both with and without
+    /// extension (e.g. `CON` matches `con.txt`).
+    Stem,
+    /// The name must start with it (e.g. `-`).
+    Prefix,
+}
+
+/// Name or pattern of names that are reserved. See [`Rules`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ReservedName {
+    /// The reserved name or prefix.
+    pub name: &'static str,
+    /// How the name is matched.
+    pub matching: NameMatch,
+}
+
+impl ReservedName {
+    /// Checks whether the given name matches this reserved name.
+    ///
+    /// # Example
+    /// ```
+    /// use filesan::{NameMatch, ReservedName};
+    ///
+    /// let con = ReservedName { name: "CON", matching: NameMatch::Stem };
+    /// assert!(con.matches("con.txt"));
+    /// assert!(!con.matches("cons"));
+    /// ```
+    pub fn matches(&self, name: &str) -> bool {
+        let (p, n) = (name.as_bytes(), self.name.as_bytes());
+        match self.matching {
+            NameMatch::Exact => p == n,
+            NameMatch::IgnoreCase => p.eq_ignore_ascii_case(n),
+            NameMatch::Stem => {
+                let stem = p.split(|b| *b == b'.').next().unwrap_or_default();
+                stem.eq_ignore_ascii_case(n)
+            }
+            NameMatch::Prefix => p.starts_with(n),
+        }
+    }
+}
+
+/// Maximum number of UTF-16 code units in name with [`Mode::FAT`].
+const FAT_MAX_LEN: usize = 255;
+
+/// Unit in which the length of name is measured. See [`Rules::max_len`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LengthUnit {
+    /// Bytes of the UTF-8 encoding (see [`crate::escape_truncated_bytes`]).
+    Bytes,
+    /// Unicode characters (see [`crate::escape_truncated`]).
+    Chars,
+    /// Code units of the UTF-16 encoding (see
+    /// [`crate::escape_truncated_utf16`]).
+    Utf16,
+}
+
+/// Description of all the constraints of mode. See [`rules`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Rules {
+    /// Disallowed characters as sorted ranges that don't overlap or touch.
+    pub disallowed: Vec<RangeInclusive<char>>,
+    /// Reserved names and patterns of names.
+    pub reserved: Vec<ReservedName>,
+    /// Characters disallowed at the end of the name.
+    pub trailing: Vec<char>,
+    /// `true` if names that differ only in case may refer to the same file
+    /// on some of the systems.
+    pub case_insensitive: bool,
+    /// Maximum length of the name if the mode limits it (e.g. 255 UTF-16
+    /// code units with [`Mode::FAT`]). The escaping functions don't enforce
+    /// it, use the truncating functions such as
+    /// [`crate::escape_truncated_utf16`].
+    pub max_len: Option<(usize, LengthUnit)>,
+}
+
+/// Describe all the constraints of the given mode.
+///
+/// The description is built from the same tables as [`crate::escape_str`]
+/// uses, so it describes exactly the characters and names that are escaped
+/// (except the escape character). Combined modes give combination of the
+/// rules.
+///
+/// [`crate::escape_str`] and [`crate::validate`] don't read the rules, they
+/// use the tables directly, so that they don't need to allocate. The
+/// agreement of the rules with the escaping is checked by tests.
+///
+/// # Example
+/// ```
+/// use filesan::{rules, LengthUnit, Mode, NameMatch, ReservedName};
+///
+/// let unix = rules(Mode::UNIX);
+/// assert_eq!(unix.disallowed, ['\0'..='\0', '/'..='/']);
+/// assert_eq!(
+///     unix.reserved[0],
+///     ReservedName { name: ".", matching: NameMatch::Exact }
+/// );
+/// assert!(unix.trailing.is_empty());
+/// assert!(!unix.case_insensitive);
+///
+/// let windows = rules(Mode::WINDOWS);
+/// assert_eq!(windows.disallowed[0], '\0'..='\x1F');
+/// assert_eq!(windows.trailing, [' ', '.']);
+/// assert!(windows.case_insensitive);
+/// assert_eq!(windows.max_len, None);
+///
+/// assert_eq!(rules(Mode::FAT).max_len, Some((255, LengthUnit::Utf16)));
+/// ```
+pub fn rules(mode: Mode) -> Rules {
+    let mut disallowed: Vec<RangeInclusive<char>> = Vec::new();
+    let mut add = |c: char| match disallowed.last_mut() {
+        Some(r) if *r.end() as u32 + 1 == c as u32 => *r = *r.start()..=c,
+        _ => disallowed.push(c..=c),
+    };
+    (0..0x80u8)
+        .map(char::from)
+        .filter(|c| !allowed(*c, mode))
+        .for_each(&mut add);
+    if !non_ascii_allowed(mode) {
+        add('\u{80}');
+        let last = disallowed.last_mut().unwrap();
+        *last = *last.start()..=char::MAX;
+    }
+
+    // The same order as in `reserved_len`.
+    let mut reserved = Vec::new();
+    let mut add = |names: &[&'static str], matching| {
+        reserved
+            .extend(names.iter().map(|&name| ReservedName { name, matching }))
+    };
+    if mode.contains(Mode::POSIX) {
+        add(&["-"], NameMatch::Prefix);
+    }
+    if mode.contains(Mode::WINDOWS) {
+        add(WINDOWS_RESERVED, NameMatch::Stem);
+    }
+    if mode.contains(Mode::CLOUD) {
+        add(&[" ", "~$"], NameMatch::Prefix);
+        add(CLOUD_RESERVED, NameMatch::IgnoreCase);
+    }
+    if mode.intersects(UNIX_LIKE) {
+        add(UNIX_RESERVED, NameMatch::Exact);
+    }
+
+    let trailing = if mode.intersects(Mode::WINDOWS) {
+        alloc::vec![' ', '.']
+    } else {
+        Vec::new()
+    };
+
+    Rules {
+        disallowed,
+        reserved,
+        trailing,
+        case_insensitive: mode.intersects(Mode::WINDOWS_MAC.union(Mode::HFS)),
+        max_len: mode
+            .contains(Mode::FAT)
+            .then_some((FAT_MAX_LEN, LengthUnit::Utf16)),
+    }
+}
@@ -0,0 +1,300 @@
+use alloc::{borrow::Cow, boxed::Box, string::String, sync::Arc};
+
+use crate::{
+    Classify, DEFAULT_ESC, EscapedChar, HexCase, Mode, Replacement,
+    allowed_overridden, escape_classified_into, split_ext, unescape_str,
+};
+
+/// Reusable configuration for escaping.
+///
+/// By default it uses [`DEFAULT_ESC`] as the escape character and
+/// [`Mode::SYSTEM`] as the mode.
+///
+/// # Example
+/// ```
+/// use filesan::{Mode, Sanitizer};
+///
+/// let san = Sanitizer::new().esc('~').mode(Mode::WINDOWS);
+/// assert!(san.needs_escape("my:file."));
+/// let name = san.escape("my:file.");
+/// assert_eq!(name, "my~3Afile~2E");
+/// assert_eq!(san.unescape(&name), "my:file.");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sanitizer {
+    esc: char,
+    mode: Mode,
+    lowercase_ext: bool,
+    hex_case: HexCase,
+    /// Shared so that cloning the sanitizer stays cheap.
+    extra_reserved: Arc<[Box<str>]>,
+    /// ASCII characters allowed regardless of the mode.
+    allow: u128,
+    /// ASCII characters disallowed regardless of the mode.
+    disallow: u128,
+}
+
+impl Sanitizer {
+    /// Create new sanitizer with the default configuration.
+    pub fn new() -> Self {
+        Self {
+            esc: DEFAULT_ESC,
+            mode: Mode::SYSTEM,
+            lowercase_ext: false,
+            hex_case: HexCase::Upper,
+            extra_reserved: Arc::new([]),
+            allow: 0,
+            disallow: 0,
+        }
+    }
+
+    /// Set the escape character.
+    pub fn esc(mut self, esc: char) -> Self {
+        self.esc = esc;
+        self
+    }
+
+    /// Set the mode.
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set whether the extension should be converted to lowercase before
+    /// escaping. Only the last extension is converted (e.g. `a.TAR.GZ`
+    /// becomes `a.TAR.gz`) and the stem is kept as it is. The conversion
+    /// uses full unicode lowercase mapping, so the extension may get longer.
+    ///
+    /// The original case can't be restored with [`Sanitizer::unescape`].
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use filesan::{Mode, Sanitizer};
+    ///
+    /// let san = Sanitizer::new().mode(Mode::WINDOWS).lowercase_ext(true);
+    /// assert_eq!(san.escape("IMG_0001.JPG"), "IMG_5F0001.jpg");
+    /// assert_eq!(san.escape("CON.TXT"), "_43ON.txt");
+    /// ```
+    pub fn lowercase_ext(mut self, lowercase: bool) -> Self {
+        self.lowercase_ext = lowercase;
+        self
+    }
+
+    /// Set the case of hex digits in escape sequences. See
+    /// [`crate::escape_str_cased`]. [`Sanitizer::unescape`] accepts both
+    /// cases regardless of this option.
+    ///
+    /// [`HexCase::Upper`] by default.
+    ///
+    /// # Example
+    /// ```
+    /// use filesan::{HexCase, Mode, Sanitizer};
+    ///
+    /// let san = Sanitizer::new()
+    ///     .mode(Mode::WINDOWS)
+    ///     .hex_case(HexCase::Lower);
+    /// assert_eq!(san.escape("a:b?"), "a_3ab_3f");
+    /// assert_eq!(san.unescape("a_3ab_3F"), "a:b?");
+    /// ```
+    pub fn hex_case(mut self, case: HexCase) -> Self {
+        self.hex_case = case;
+        self
+    }
+
+    /// Set additional reserved names. The names are matched in the same way
+    /// as the windows reserved names: case insensitive and both with and
+    /// without extension (e.g. `Thumbs.db` also matches `thumbs.DB.txt`).
+    /// The first character of matching names is escaped in the same way as
+    /// for the reserved names of the mode.
+    ///
+    /// The names replace the previously set names. Empty by default.
+    ///
+    /// # Example
+    /// ```
+    /// use filesan::{Mode, Sanitizer};
+    ///
+    /// let san = Sanitizer::new()
+    ///     .mode(Mode::UNIX)
+    ///     .extra_reserved([".git", "Thumbs.db"]);
+    /// assert_eq!(san.escape(".git"), "_2Egit");
+    /// assert_eq!(san.escape("thumbs.DB.bak"), "_74humbs.DB.bak");
+    /// assert_eq!(san.escape(".gitignore"), ".gitignore");
+    /// assert_eq!(san.unescape("_2Egit"), ".git");
+    /// ```
+    pub fn extra_reserved(
+        mut self,
+        names: impl IntoIterator<Item = impl Into<Box<str>>>,
+    ) -> Self {
+        self.extra_reserved = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Allow the given ASCII character regardless of the mode (e.g. `:` with
+    /// [`Mode::WINDOWS`]).
+    ///
+    /// The overrides win over the disallowed characters of the mode, and if
+    /// the same character is given to both [`Sanitizer::allow`] and
+    /// [`Sanitizer::disallow`], the last call wins. The escape character is
+    /// always escaped. Reserved names and disallowed trailing characters are
+    /// not affected, so for example trailing spaces are still escaped with
+    /// [`Mode::WINDOWS`] even if ` ` is allowed.
+    ///
+    /// The result may not be valid on the systems of the mode if disallowed
+    /// characters are allowed.
+    ///
+    /// # Panics
+    /// If `c` is not ASCII character.
+    ///
+    /// # Example
+    /// ```
+    /// use filesan::{Mode, Sanitizer};
+    ///
+    /// let san = Sanitizer::new().mode(Mode::WINDOWS).allow(':');
+    /// assert_eq!(san.escape("C:a?"), "C:a_3F");
+    /// ```
+    pub fn allow(mut self, c: char) -> Self {
+        let bit = ascii_bit(c);
+        self.allow |= bit;
+        self.disallow &= !bit;
+        self
+    }
+
+    /// Disallow the given ASCII character regardless of the mode (e.g. `#`
+    /// with [`Mode::MAC`]). Disallowed characters are escaped. See
+    /// [`Sanitizer::allow`] for the precedence of the overrides.
+    ///
+    /// # Panics
+    /// If `c` is not ASCII character.
+    ///
+    /// # Example
+    /// ```
+    /// use filesan::{Mode, Sanitizer};
+    ///
+    /// let san = Sanitizer::new().mode(Mode::MAC).disallow('#');
+    /// assert_eq!(san.escape("a#b:c"), "a_23b_3Ac");
+    /// assert_eq!(san.unescape("a_23b_3Ac"), "a#b:c");
+    /// ```
+    pub fn disallow(mut self, c: char) -> Self {
+        let bit = ascii_bit(c);
+        self.disallow |= bit;
+        self.allow &= !bit;
+        self
+    }
+
+    /// Checks whether the character is allowed by the mode and the overrides
+    /// from [`Sanitizer::allow`] and [`Sanitizer::disallow`].
+    pub fn allowed(&self, c: char) -> bool {
+        allowed_overridden(c, self.mode, self.allow, self.disallow)
+    }
+
+    /// Get the escape character.
+    pub fn get_esc(&self) -> char {
+        self.esc
+    }
+
+    /// Get the mode.
+    pub fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Get whether the extension is converted to lowercase.
+    pub fn get_lowercase_ext(&self) -> bool {
+        self.lowercase_ext
+    }
+
+    /// Get the case of hex digits in escape sequences.
+    pub fn get_hex_case(&self) -> HexCase {
+        self.hex_case
+    }
+
+    /// Get the additional reserved names.
+    pub fn get_extra_reserved(&self) -> &[Box<str>] {
+        &self.extra_reserved
+    }
+
+    /// Escape the given string. See [`crate::escape_str`].
+    pub fn escape(&self, p: &str) -> String {
+        let p = self.normalize(p);
+        let mut res = String::new();
+        let cls = self.classify(&p);
+        escape_classified_into(
+            cls,
+            self.esc,
+            self.hex_case,
+            &mut res,
+            |_, _| Replacement::Hex,
+        );
+        // Escaping of characters doesn't depend on whether the previous
+        // characters are escaped, so the first character may be escaped
+        // afterwards. The escape character is always escaped.
+        match p.chars().next() {
+            Some(c)
+                if c != self.esc
+                    && res.starts_with(c)
+                    && self.is_extra_reserved(&p) =>
+            {
+                String::from(&*EscapedChar::escaped_cased(
+                    c,
+                    self.esc,
+                    self.hex_case,
+                )) + &res[c.len_utf8()..]
+            }
+            _ => res,
+        }
+    }
+
+    /// Reverse the escaping. See [`unescape_str`].
+    pub fn unescape(&self, s: &str) -> String {
+        unescape_str(s, self.esc)
+    }
+
+    /// Checks whether the string needs to be escaped. See
+    /// [`crate::needs_escape`].
+    /// This is also `true` if the extension would be converted to lowercase.
+    pub fn needs_escape(&self, p: &str) -> bool {
+        let p = self.normalize(p);
+        matches!(p, Cow::Owned(_))
+            || self.is_extra_reserved(&p)
+            || self.classify(&p).any(|(_, e)| e)
+    }
+
+    /// Classify the characters of the name with the configuration.
+    fn classify<'a>(&self, p: &'a str) -> Classify<'a> {
+        Classify::new(p, self.esc, self.mode)
+            .with_overrides(self.allow, self.disallow)
+    }
+
+    /// Checks whether the name is one of the additional reserved names.
+    fn is_extra_reserved(&self, p: &str) -> bool {
+        self.extra_reserved.iter().any(|n| {
+            p.len() >= n.len()
+                && p.as_bytes()[..n.len()].eq_ignore_ascii_case(n.as_bytes())
+                && matches!(p.as_bytes().get(n.len()), None | Some(b'.'))
+        })
+    }
+
+    /// Apply the normalization of the name before it is escaped.
+    fn normalize<'a>(&self, p: &'a str) -> Cow<'a, str> {
+        let (stem, ext) = split_ext(p);
+        let lower = ext.chars().flat_map(char::to_lowercase);
+        if !self.lowercase_ext || lower.clone().eq(ext.chars()) {
+            return Cow::Borrowed(p);
+        }
+        let mut res = String::from(stem);
+        res.extend(lower);
+        Cow::Owned(res)
+    }
+}
+
+/// Get the bit of ASCII character in the override masks.
+fn ascii_bit(c: char) -> u128 {
+    assert!(c.is_ascii(), "only ASCII characters may be overridden");
+    1 << c as u32
+}
+
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,69 @@
+use core::fmt::Display;
+
+use crate::{Classify, EscapedChar, Mode, escaped_len};
+
+/// Error returned by [`escape_to_slice`] when the output buffer is too small.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferTooSmall {
+    /// Length in bytes of the whole escaped string.
+    pub required: usize,
+}
+
+impl Display for BufferTooSmall {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Buffer is too small, the escaped string has {} bytes.",
+            self.required
+        )
+    }
+}
+
+impl core::error::Error for BufferTooSmall {}
+
+/// Escape the given string into the given buffer without allocating.
+///
+/// The UTF-8 bytes of the escaped string are the same as the result of
+/// [`crate::escape_str`] with the same arguments. Returns the number of bytes
+/// written to `out`.
+///
+/// # Errors
+/// [`BufferTooSmall`] with the required length if the escaped string doesn't
+/// fit into `out`. The contents of `out` are unspecified in that case.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_to_slice, BufferTooSmall, Mode};
+///
+/// let mut buf = [0; 16];
+/// let len = escape_to_slice("NUL.a:", '_', Mode::WINDOWS, &mut buf).unwrap();
+/// assert_eq!(&buf[..len], b"_4EUL.a_3A");
+///
+/// assert_eq!(
+///     escape_to_slice("NUL.a:", '_', Mode::WINDOWS, &mut buf[..4]),
+///     Err(BufferTooSmall { required: 10 }),
+/// );
+/// ```
+pub fn escape_to_slice(
+    s: &str,
+    esc: char,
+    mode: Mode,
+    out: &mut [u8],
+) -> Result<usize, BufferTooSmall> {
+    let mut len = 0;
+    for (c, e) in Classify::new(s, esc, mode) {
+        let chr = if e {
+            EscapedChar::escaped(c, esc)
+        } else {
+            EscapedChar::raw(c)
+        };
+        let Some(dst) = out.get_mut(len..len + chr.len()) else {
+            return Err(BufferTooSmall {
+                required: escaped_len(s, esc, mode),
+            });
+        };
+        dst.copy_from_slice(chr.as_bytes());
+        len += chr.len();
+    }
+    Ok(len)
+}
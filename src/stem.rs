@@ -0,0 +1,117 @@
+use alloc::string::String;
+use core::fmt::Display;
+
+use crate::{
+    Mode, allowed, decode_hex, escape_str, split_ext, trailing_start,
+};
+
+/// Error returned by [`escape_stem_only`] if the extension is not valid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidExtension {
+    ext: String,
+}
+
+impl InvalidExtension {
+    /// Get the invalid extension (without the dot).
+    pub fn extension(&self) -> &str {
+        &self.ext
+    }
+}
+
+impl Display for InvalidExtension {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Invalid extension `{}`.", self.ext)
+    }
+}
+
+impl core::error::Error for InvalidExtension {}
+
+/// Escape the stem of the name in the same way as [`crate::escape_str`] and
+/// keep the extension as it is.
+///
+/// The extension is the part of the name after the last dot that is not at
+/// the start of the name. The extension is not escaped, so it is checked
+/// that it doesn't need to be escaped instead. Reserved names are detected
+/// from the stem and the characters at the end of the stem are not
+/// considered to be at the end of the name.
+///
+/// If the name has no extension, the whole name is escaped. The result is
+/// always the same as the result of [`crate::escape_str`], so it can be
+/// unescaped with [`crate::unescape_str`].
+///
+/// # Errors
+/// - [`InvalidExtension`] if the extension contains disallowed characters
+///   or the escape character, if it ends with character that is disallowed
+///   at the end of the name or if the dot would be escaped.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_stem_only, Mode};
+///
+/// assert_eq!(
+///     escape_stem_only("a: b .mp3", '_', Mode::WINDOWS).unwrap(),
+///     "a_3A b .mp3"
+/// );
+/// assert_eq!(
+///     escape_stem_only("CON.flac", '_', Mode::WINDOWS).unwrap(),
+///     "_43ON.flac"
+/// );
+/// assert!(escape_stem_only("song.mp:3", '_', Mode::WINDOWS).is_err());
+/// ```
+pub fn escape_stem_only(
+    name: &str,
+    esc: char,
+    mode: Mode,
+) -> Result<String, InvalidExtension> {
+    if let Some(ext) = split_ext(name).1.strip_prefix('.')
+        && !ext.is_empty()
+        && (esc == '.'
+            || !allowed('.', mode)
+            || ext.chars().any(|c| c == esc || !allowed(c, mode))
+            || trailing_start(ext.as_bytes(), mode) != ext.len())
+    {
+        return Err(InvalidExtension { ext: ext.into() });
+    }
+
+    // The extension doesn't need to be escaped, so it is kept as it is by
+    // `escape_str`.
+    Ok(escape_str(name, esc, mode))
+}
+
+/// Split name escaped with [`crate::escape_str`] to stem and extension.
+///
+/// The name is split at the last dot that is not at the start of the name and
+/// the extension is returned without the dot. Escaped dots are not
+/// considered to be separators and the name is never split inside escape
+/// sequence, even if the escape character is `.`. Unlike
+/// `Path::extension`, this works correctly on escaped names.
+///
+/// # Example
+/// ```
+/// use filesan::split_escaped;
+///
+/// assert_eq!(split_escaped("a_2Fb.txt", '_'), ("a_2Fb", Some("txt")));
+/// assert_eq!(split_escaped("a_2E", '_'), ("a_2E", None));
+/// assert_eq!(split_escaped("a.2Eb", '.'), ("a.2Eb", None));
+/// assert_eq!(split_escaped("_2E.", '_'), ("_2E", Some("")));
+/// ```
+pub fn split_escaped(name: &str, esc: char) -> (&str, Option<&str>) {
+    let mut dot = None;
+    let mut rest = name;
+    while let Some(c) = rest.chars().next() {
+        let idx = name.len() - rest.len();
+        rest = &rest[c.len_utf8()..];
+        if c == esc {
+            if let Ok((_, len)) = decode_hex(rest, idx) {
+                rest = &rest[len..];
+            }
+        } else if c == '.' && idx != 0 {
+            dot = Some(idx);
+        }
+    }
+
+    match dot {
+        Some(i) => (&name[..i], Some(&name[i + 1..])),
+        None => (name, None),
+    }
+}
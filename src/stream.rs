@@ -0,0 +1,268 @@
+use alloc::string::String;
+use core::iter::FusedIterator;
+
+use crate::{
+    Classify, Mode, ReservedPrefix, allowed, reserved_prefix, write_escape,
+};
+
+/// Escape the characters from the given iterator so that they may be used as
+/// valid path on the given systems.
+///
+/// The result is always the same as the result of [`crate::escape_str`] on
+/// the collected string, but the input doesn't need to be collected into
+/// string first.
+///
+/// To detect reserved names, the start of the name is buffered until it is
+/// clear whether the name is reserved. This is at most as long as the longest
+/// reserved name of the mode: 11 characters (`desktop.ini`) with
+/// [`Mode::CLOUD`] and 7 characters (`CONOUT$`) otherwise, because it is
+/// clear that longer names are not reserved. With [`Mode::WINDOWS`], runs of
+/// ` ` and `.` are also buffered until it is clear whether they are at the
+/// end of the name.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_chars, Mode};
+///
+/// let id = 5;
+/// let name = escape_chars(
+///     "report:".chars().chain(id.to_string().chars()).chain(".".chars()),
+///     '_',
+///     Mode::WINDOWS,
+/// );
+/// assert_eq!(name, "report_3A5_2E");
+/// ```
+pub fn escape_chars<I: IntoIterator<Item = char>>(
+    iter: I,
+    esc: char,
+    mode: Mode,
+) -> String {
+    let mut res = StreamEscaper::new(esc, mode);
+    for c in iter {
+        res.push(c);
+    }
+    res.finish()
+}
+
+/// Escapes string that is given in chunks.
+///
+/// The result is always the same as the result of [`crate::escape_str`] on
+/// the concatenated chunks, regardless of how the string is split. The
+/// characters are buffered in the same way as in [`escape_chars`], so the
+/// start of the name is buffered until it is clear whether the name is
+/// reserved: at most 11 characters (`desktop.ini`) with [`Mode::CLOUD`] and
+/// 7 characters (`CONOUT$`) otherwise.
+///
+/// # Example
+/// ```
+/// use filesan::{Mode, StreamEscaper};
+///
+/// let mut esc = StreamEscaper::new('_', Mode::WINDOWS);
+/// esc.push_str("N");
+/// esc.push_str("UL.a:");
+/// esc.push_str("b.");
+/// assert_eq!(esc.finish(), "_4EUL.a_3Ab_2E");
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamEscaper {
+    cls: StreamClassify,
+    res: String,
+}
+
+impl StreamEscaper {
+    /// Create new escaper. See [`crate::escape_str`] for the meaning of the
+    /// arguments.
+    pub fn new(esc: char, mode: Mode) -> Self {
+        Self {
+            cls: StreamClassify::new(esc, mode),
+            res: String::new(),
+        }
+    }
+
+    /// Push next chunk of the string.
+    pub fn push_str(&mut self, chunk: &str) {
+        for c in chunk.chars() {
+            self.push(c);
+        }
+    }
+
+    /// Push next character of the string.
+    pub fn push(&mut self, c: char) {
+        let esc = self.cls.esc;
+        self.cls
+            .push(c, &mut |c, e| push_char(&mut self.res, c, e, esc));
+    }
+
+    /// Finish the string and get the escaped result.
+    pub fn finish(self) -> String {
+        let Self { cls, mut res } = self;
+        let esc = cls.esc;
+        cls.finish(&mut |c, e| push_char(&mut res, c, e, esc));
+        res
+    }
+}
+
+/// Iterator adapter that escapes characters of another iterator lazily.
+///
+/// Collecting the iterator always gives the same result as
+/// [`crate::escape_str`] on the collected input, including reserved names
+/// and characters at the end of the name. To decide about them, some of the
+/// characters are buffered in the same way as in [`escape_chars`], so the
+/// escaped characters may be yielded later than the input characters are
+/// read.
+///
+/// # Example
+/// ```
+/// use filesan::{EscapeChars, Mode};
+///
+/// let res: String =
+///     EscapeChars::new("nul.a:b".chars(), '_', Mode::WINDOWS).collect();
+/// assert_eq!(res, "_6Eul.a_3Ab");
+///
+/// let res: Vec<_> = EscapeChars::new("a/b".chars(), '_', Mode::UNIX)
+///     .map(|c| c.to_ascii_uppercase())
+///     .collect();
+/// assert_eq!(res, ['A', '_', '2', 'F', 'B']);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EscapeChars<I> {
+    iter: I,
+    /// [`None`] after the input is exhausted.
+    cls: Option<StreamClassify>,
+    /// Escaped characters that are ready.
+    buf: String,
+    /// Position of the next character in `buf`.
+    pos: usize,
+}
+
+impl<I: Iterator<Item = char>> EscapeChars<I> {
+    /// Create new iterator that escapes the characters of `iter`. See
+    /// [`crate::escape_str`] for the meaning of the arguments.
+    pub fn new<T>(iter: T, esc: char, mode: Mode) -> Self
+    where
+        T: IntoIterator<IntoIter = I>,
+    {
+        Self {
+            iter: iter.into_iter(),
+            cls: Some(StreamClassify::new(esc, mode)),
+            buf: String::new(),
+            pos: 0,
+        }
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for EscapeChars<I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+            let buf = &mut self.buf;
+            match (self.iter.next(), &mut self.cls) {
+                (Some(c), Some(cls)) => {
+                    let esc = cls.esc;
+                    cls.push(c, &mut |c, e| push_char(buf, c, e, esc));
+                }
+                (_, cls) => {
+                    let cls = cls.take()?;
+                    let esc = cls.esc;
+                    cls.finish(&mut |c, e| push_char(buf, c, e, esc));
+                }
+            }
+        }
+
+        let c = self.buf[self.pos..].chars().next()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+}
+
+impl<I: Iterator<Item = char>> FusedIterator for EscapeChars<I> {}
+
+fn push_char(res: &mut String, c: char, e: bool, esc: char) {
+    if e {
+        _ = write_escape(res, c, esc);
+    } else {
+        res.push(c);
+    }
+}
+
+/// Decides which characters should be escaped without knowing the whole
+/// string in advance.
+#[derive(Debug, Clone)]
+pub(crate) struct StreamClassify {
+    esc: char,
+    mode: Mode,
+    /// Start of the name while it is not decided whether the name is
+    /// reserved.
+    head: Option<String>,
+    /// Last characters that may need to be escaped because they are
+    /// disallowed at the end.
+    tail: String,
+}
+
+impl StreamClassify {
+    pub(crate) fn new(esc: char, mode: Mode) -> Self {
+        Self {
+            esc,
+            mode,
+            head: Some(String::new()),
+            tail: String::new(),
+        }
+    }
+
+    /// Push next character. `out` is called with characters that are ready
+    /// and whether they should be escaped.
+    pub(crate) fn push(&mut self, c: char, out: &mut impl FnMut(char, bool)) {
+        if let Some(head) = &mut self.head {
+            head.push(c);
+            let reserved = match reserved_prefix(head.as_bytes(), self.mode) {
+                ReservedPrefix::Maybe => return,
+                ReservedPrefix::No => 0,
+                ReservedPrefix::Yes(l) => l,
+            };
+            let head = self.head.take().unwrap();
+            for (i, c) in head.char_indices() {
+                if i == 0 && reserved != 0 {
+                    out(c, true);
+                } else {
+                    self.push_normal(c, out);
+                }
+            }
+        } else {
+            self.push_normal(c, out);
+        }
+    }
+
+    /// Finish the string. `out` is called with all the remaining characters.
+    pub(crate) fn finish(self, out: &mut impl FnMut(char, bool)) {
+        if let Some(head) = &self.head {
+            for (c, e) in Classify::new(head, self.esc, self.mode) {
+                out(c, e);
+            }
+        } else {
+            for c in self.tail.chars() {
+                out(c, true);
+            }
+        }
+    }
+
+    fn push_normal(&mut self, c: char, out: &mut impl FnMut(char, bool)) {
+        if self.mode.intersects(Mode::WINDOWS)
+            && !allowed(c, Mode::WINDOWS_END)
+        {
+            self.tail.push(c);
+        } else {
+            self.flush_tail(out);
+            out(c, c == self.esc || !allowed(c, self.mode));
+        }
+    }
+
+    fn flush_tail(&mut self, out: &mut impl FnMut(char, bool)) {
+        let (esc, mode) = (self.esc, self.mode);
+        for c in self.tail.drain(..) {
+            out(c, c == esc || !allowed(c, mode));
+        }
+    }
+}
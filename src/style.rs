@@ -0,0 +1,202 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::{Classify, EscapedChar, Mode, Reason, allowed, unescape_str};
+
+/// Format of escape sequences used by [`escape_str_styled`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum EscapeStyle {
+    /// The escape character followed by hex value of the character (e.g.
+    /// `_2F`). This is the format of [`crate::escape_str`] (see the
+    /// [crate documentation](crate) for details).
+    ///
+    /// Unique inputs generate unique outputs.
+    #[default]
+    HexSuffix,
+    /// URL style percent encoding. Each byte of the UTF-8 encoding of the
+    /// character is encoded as `%` followed by two hex digits (e.g. `%2F`
+    /// or `%C5%BE` for `ž`). The character `%` itself is encoded as `%25`.
+    /// The escape character is always `%`.
+    ///
+    /// Unique inputs generate unique outputs. The result is valid only if
+    /// `%` is allowed by the mode (it is not allowed with [`Mode::POSIX`]).
+    Percent,
+    /// Rust style unicode escape (e.g. `\u{2F}`). The character `\` itself
+    /// is encoded as `\u{5C}`. The escape character is always `\`.
+    ///
+    /// Unique inputs generate unique outputs, but the result contains `\`
+    /// which is not allowed with [`Mode::WINDOWS`]. This is mostly useful
+    /// for debugging.
+    UnicodeBraces,
+    /// The characters are removed.
+    ///
+    /// This is lossy, different inputs may generate the same output and the
+    /// result can't be unescaped. The result may also be empty or reserved
+    /// name (e.g. `..` with [`Mode::UNIX`] and [`Mode::WINDOWS`] becomes
+    /// empty).
+    Remove,
+}
+
+impl EscapeStyle {
+    /// Get the character that starts escape sequences. `esc` is the escape
+    /// character given by the user.
+    fn esc(&self, esc: char) -> Option<char> {
+        match self {
+            Self::HexSuffix => Some(esc),
+            Self::Percent => Some('%'),
+            Self::UnicodeBraces => Some('\\'),
+            Self::Remove => None,
+        }
+    }
+}
+
+/// Escape the given string in the same way as [`crate::escape_str`], but
+/// with the given style of escape sequences. See [`EscapeStyle`] for the
+/// description of the styles.
+///
+/// `esc` is the escape character of [`EscapeStyle::HexSuffix`]. The other
+/// styles have fixed escape character (or none), so `esc` is ignored and it
+/// is not escaped.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_styled, EscapeStyle, Mode};
+///
+/// let esc = |s, style| escape_str_styled(s, '_', Mode::WINDOWS, style);
+/// assert_eq!(esc("a/b.", EscapeStyle::HexSuffix), "a_2Fb_2E");
+/// assert_eq!(esc("a_b.", EscapeStyle::Percent), "a_b%2E");
+/// assert_eq!(esc("a/b%.", EscapeStyle::Percent), "a%2Fb%25%2E");
+/// assert_eq!(esc("NUL", EscapeStyle::UnicodeBraces), "\\u{4E}UL");
+/// assert_eq!(esc("a/b.", EscapeStyle::Remove), "ab");
+/// ```
+pub fn escape_str_styled(
+    p: &str,
+    esc: char,
+    mode: Mode,
+    style: EscapeStyle,
+) -> String {
+    // No character is the escape character with `EscapeStyle::Remove`, so
+    // characters reported as escape characters are checked again.
+    let mut cls = Classify::new(p, style.esc(esc).unwrap_or('\0'), mode);
+    let mut res = String::new();
+    while let Some((c, r)) = cls.next_reason() {
+        match (r, style) {
+            (None, _) => res.push(c),
+            (Some(Reason::Escape), EscapeStyle::Remove)
+                if allowed(c, mode) =>
+            {
+                res.push(c)
+            }
+            (_, EscapeStyle::HexSuffix) => {
+                res += &EscapedChar::escaped(c, esc)
+            }
+            (_, EscapeStyle::Percent) => {
+                for b in c.encode_utf8(&mut [0; 4]).bytes() {
+                    _ = write!(res, "%{b:02X}");
+                }
+            }
+            (_, EscapeStyle::UnicodeBraces) => {
+                _ = write!(res, "\\u{{{:X}}}", c as u32);
+            }
+            (_, EscapeStyle::Remove) => {}
+        }
+    }
+    res
+}
+
+/// Reverse the escaping done by [`escape_str_styled`] with the same escape
+/// character and style. `esc` is used only with [`EscapeStyle::HexSuffix`].
+///
+/// Invalid escape sequences are kept as they are in the same way as in
+/// [`crate::unescape_str`]. Both uppercase and lowercase hex digits are
+/// accepted. [`EscapeStyle::Remove`] is not reversible, so the string is
+/// returned unchanged.
+///
+/// # Example
+/// ```
+/// use filesan::{unescape_styled, EscapeStyle};
+///
+/// let un = |s, style| unescape_styled(s, '_', style);
+/// assert_eq!(un("a_2Fb_5F", EscapeStyle::HexSuffix), "a/b_");
+/// assert_eq!(un("a%2Fb%C5%BE_2F", EscapeStyle::Percent), "a/bž_2F");
+/// assert_eq!(un("\\u{4E}UL\\u{2f}", EscapeStyle::UnicodeBraces), "NUL/");
+/// ```
+pub fn unescape_styled(s: &str, esc: char, style: EscapeStyle) -> String {
+    match style {
+        EscapeStyle::HexSuffix => unescape_str(s, esc),
+        EscapeStyle::Percent => unescape_percent(s),
+        EscapeStyle::UnicodeBraces => unescape_braces(s),
+        EscapeStyle::Remove => s.into(),
+    }
+}
+
+fn unescape_percent(s: &str) -> String {
+    let mut res = String::new();
+    let mut rest = s;
+
+    while let Some(i) = rest.find('%') {
+        res += &rest[..i];
+        // Decode the whole run of escaped bytes, because characters may be
+        // encoded with multiple bytes.
+        let src = &rest[i..];
+        let mut run = src;
+        let mut bytes = Vec::new();
+        while let Some(b) = run.strip_prefix('%').and_then(hex_byte) {
+            bytes.push(b);
+            run = &run[3..];
+        }
+
+        if bytes.is_empty() {
+            res.push('%');
+            rest = &src[1..];
+            continue;
+        }
+
+        // Invalid UTF-8 is kept in its escaped form.
+        let mut pos = 0;
+        for chunk in bytes.utf8_chunks() {
+            res += chunk.valid();
+            pos += chunk.valid().len() * 3;
+            let invalid = chunk.invalid().len() * 3;
+            res += &src[pos..pos + invalid];
+            pos += invalid;
+        }
+        rest = run;
+    }
+
+    res + rest
+}
+
+/// Parse byte from the two hex digits at the start of the string.
+fn hex_byte(s: &str) -> Option<u8> {
+    let hex = s.get(..2)?;
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u8::from_str_radix(hex, 16).ok()
+}
+
+fn unescape_braces(s: &str) -> String {
+    let mut res = String::new();
+    let mut rest = s;
+
+    while let Some((pre, post)) = rest.split_once("\\u{") {
+        res += pre;
+        let c = post.split_once('}').and_then(|(hex, _)| {
+            let valid = (1..=6).contains(&hex.len())
+                && hex.bytes().all(|b| b.is_ascii_hexdigit());
+            let n = u32::from_str_radix(hex, 16).ok().filter(|_| valid)?;
+            char::from_u32(n).map(|c| (c, hex.len()))
+        });
+
+        if let Some((c, len)) = c {
+            res.push(c);
+            rest = &post[len + 1..];
+        } else {
+            res += "\\u{";
+            rest = post;
+        }
+    }
+
+    res + rest
+}
@@ -0,0 +1,114 @@
+use alloc::string::String;
+
+use crate::{Mode, allowed, cloud_reserved_prefix, reserved_len, split_ext};
+
+/// Suggest valid name that is similar to the given name. This is useful to
+/// suggest a fix to the user instead of escaping the name.
+///
+/// The changes are made to be readable instead of reversible:
+/// - Disallowed characters are replaced with `-`.
+/// - Characters that are disallowed at the end of the name are removed.
+/// - `_file` is appended to the reserved part of reserved names (e.g. `CON`
+///   becomes `CON_file` and `nul.txt` becomes `nul_file.txt`). Names that
+///   are reserved because of their prefix (`-` with [`Mode::POSIX`], ` `
+///   and `~$` with [`Mode::CLOUD`]) are prefixed with `file` instead (e.g.
+///   `~$a.docx` becomes `file~$a.docx`).
+/// - Empty name becomes `file`.
+///
+/// The result is always valid name for the given mode, so
+/// [`crate::escape_str`] wouldn't escape it (unless it contains the escape
+/// character). Unlike [`crate::escape_str`], this is lossy and different
+/// names may have the same suggestion, so it shouldn't be used to create
+/// names automatically.
+///
+/// # Example
+/// ```
+/// use filesan::{suggest, Mode};
+///
+/// assert_eq!(suggest("report.", Mode::WINDOWS), "report");
+/// assert_eq!(suggest("a:b?.txt", Mode::WINDOWS), "a-b-.txt");
+/// assert_eq!(suggest("con.txt", Mode::WINDOWS), "con_file.txt");
+/// assert_eq!(suggest("..", Mode::UNIX), ".._file");
+/// ```
+pub fn suggest(name: &str, mode: Mode) -> String {
+    let mut res: String = name
+        .chars()
+        .map(|c| if allowed(c, mode) { c } else { '-' })
+        .collect();
+
+    if mode.intersects(Mode::WINDOWS) {
+        let len = res
+            .trim_end_matches(|c| !allowed(c, Mode::WINDOWS_END))
+            .len();
+        res.truncate(len);
+    }
+
+    match reserved_len(res.as_bytes(), mode) {
+        _ if res.is_empty() => res.push_str("file"),
+        0 => {}
+        _ if has_reserved_prefix(&res, mode) => res.insert_str(0, "file"),
+        l => res.insert_str(l, "_file"),
+    }
+
+    res
+}
+
+/// Checks whether the name is reserved because of its prefix, so appending to
+/// the reserved part wouldn't make it valid.
+fn has_reserved_prefix(name: &str, mode: Mode) -> bool {
+    mode.contains(Mode::POSIX) && name.starts_with('-')
+        || mode.contains(Mode::CLOUD)
+            && cloud_reserved_prefix(name.as_bytes()).is_some()
+}
+
+/// Create readable slug from the name. This is useful for names shown to
+/// users, such as names of downloaded files.
+///
+/// Whitespace, dashes and disallowed characters are separators. Runs of
+/// separators are replaced with single `-` and separators at the start and
+/// at the end of the stem and of the extension are removed. The extension
+/// is preserved. The result is then made valid in the same way as in
+/// [`suggest`], so reserved names get `_file` (e.g. `con.docx` becomes
+/// `con_file.docx`) and empty result becomes `file`.
+///
+/// Unlike [`crate::escape_str`], this is lossy. Many names have the same slug
+/// and the original name can't be recovered, so this is meant for names that
+/// are shown to users and not to create unique names automatically.
+///
+/// # Example
+/// ```
+/// use filesan::{slugify, Mode};
+///
+/// assert_eq!(
+///     slugify("Meeting Notes: Q3/Q4 – Draft?.docx", Mode::WINDOWS),
+///     "Meeting-Notes-Q3-Q4-Draft.docx"
+/// );
+/// assert_eq!(slugify(" con .docx", Mode::WINDOWS), "con_file.docx");
+/// ```
+pub fn slugify(name: &str, mode: Mode) -> String {
+    let (stem, ext) = split_ext(name);
+    let mut res = slug(stem, mode);
+    let ext = slug(ext.strip_prefix('.').unwrap_or(""), mode);
+    if !ext.is_empty() {
+        res.push('.');
+        res += &ext;
+    }
+    suggest(&res, mode)
+}
+
+/// Join the parts of `s` between separators with `-`.
+fn slug(s: &str, mode: Mode) -> String {
+    let mut res = String::new();
+    let is_sep = |c: char| {
+        c.is_whitespace()
+            || matches!(c, '-' | '\u{2010}'..='\u{2015}' | '\u{2212}')
+            || !allowed(c, mode)
+    };
+    for part in s.split(is_sep).filter(|p| !p.is_empty()) {
+        if !res.is_empty() {
+            res.push('-');
+        }
+        res += part;
+    }
+    res
+}
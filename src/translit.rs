@@ -0,0 +1,91 @@
+use alloc::string::String;
+
+use crate::{Mode, escape_str};
+
+/// ASCII letters for the characters `U+00C0` - `U+017F`. `?` means that the
+/// character has no single letter transliteration.
+const LATIN: &str = concat!(
+    "AAAAAA?CEEEEIIIIDNOOOOO?OUUUUY??aaaaaa?ceeeeiiiidnooooo?ouuuuy?y",
+    "AaAaAaCcCcCcCcDdDdEeEeEeEeEeGgGgGgGgHhHhIiIiIiIiIiIiJjKkkLlLlLlL",
+    "lLlNnNnNnnNnOoOoOo??RrRrRrSsSsSsSsTtTtTtUuUuUuUuUuUuWwYyYZzZzZzs",
+);
+
+/// Transliterate the string to ASCII (e.g. `é` becomes `e` and `ß` becomes
+/// `ss`).
+///
+/// Latin letters with diacritics, ligatures, combining diacritical marks
+/// (they are removed), dashes, quotes and special spaces are
+/// transliterated. Other characters that are not ASCII (e.g. CJK characters
+/// or symbols) are kept as they are.
+///
+/// # Example
+/// ```
+/// use filesan::transliterate;
+///
+/// assert_eq!(transliterate("Žluťoučký kůň"), "Zlutoucky kun");
+/// assert_eq!(transliterate("Straße – e\u{301}"), "Strasse - e");
+/// assert_eq!(transliterate("☃"), "☃");
+/// ```
+pub fn transliterate(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match translit_char(c) {
+            Some(t) => res += t,
+            None => res.push(c),
+        }
+    }
+    res
+}
+
+/// Escape the given string in the same way as [`crate::escape_str`] with
+/// [`Mode::ASCII_ONLY`], but [`transliterate`] it first. Characters that
+/// can't be transliterated are escaped, so the result is pure ASCII (if the
+/// escape character is ASCII).
+///
+/// This is lossy. Different names may give the same result (e.g. `é` and
+/// `e`) and [`crate::unescape_str`] gives the transliterated name and not
+/// the original name.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str_translit, Mode};
+///
+/// assert_eq!(
+///     escape_str_translit("Café: ☃.txt", '_', Mode::WINDOWS),
+///     "Cafe_3A _u2603.txt"
+/// );
+/// assert_eq!(escape_str_translit("ÑUL", '_', Mode::WINDOWS), "_4EUL");
+/// ```
+pub fn escape_str_translit(p: &str, esc: char, mode: Mode) -> String {
+    escape_str(&transliterate(p), esc, mode | Mode::ASCII_ONLY)
+}
+
+/// Get the ASCII transliteration of the character. Returns [`None`] if the
+/// character is ASCII or if it has no transliteration.
+fn translit_char(c: char) -> Option<&'static str> {
+    let res = match c {
+        // Combining diacritical marks.
+        '\u{300}'..='\u{36F}' => "",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Þ' => "TH",
+        'þ' => "th",
+        'ß' => "ss",
+        'Ĳ' => "IJ",
+        'ĳ' => "ij",
+        'Œ' => "OE",
+        'œ' => "oe",
+        '×' => "x",
+        '\u{C0}'..='\u{17F}' => {
+            let i = c as usize - 0xC0;
+            Some(&LATIN[i..i + 1]).filter(|s| *s != "?")?
+        }
+        '\u{A0}' | '\u{2000}'..='\u{200A}' | '\u{202F}' => " ",
+        '\u{2010}'..='\u{2015}' | '\u{2212}' => "-",
+        '\u{2018}'..='\u{201B}' => "'",
+        '\u{201C}'..='\u{201F}' => "\"",
+        '\u{2026}' => "...",
+        _ => return None,
+    };
+    Some(res)
+}
@@ -0,0 +1,262 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    EscapedChar, Mode, Position, allowed, escape_str, reserved_len,
+    trailing_start,
+};
+
+/// Escape the given string in the same way as [`crate::escape_str`] and
+/// truncate the result so that it has at most `max_chars` characters.
+///
+/// If the name has extension (the part after the last dot that is not at the
+/// start of the name), it is preserved and only the stem is truncated. If
+/// there is no space for at least one character of the stem, the extension
+/// is not preserved and the whole name is truncated from the end. If not even
+/// the first character fits, the result is empty.
+///
+/// The result is always the escaped version of the truncated name, so escape
+/// sequences are never split, and the truncated name can be retrieved with
+/// [`crate::unescape_str`].
+///
+/// # Example
+/// ```
+/// use filesan::{escape_truncated, Mode};
+///
+/// assert_eq!(
+///     escape_truncated("long:name.txt", '_', Mode::WINDOWS, 10),
+///     "long.txt",
+/// );
+/// assert_eq!(
+///     escape_truncated("long:name.txt", '_', Mode::WINDOWS, 5),
+///     "l.txt",
+/// );
+/// assert_eq!(
+///     escape_truncated("long:name.txt", '_', Mode::WINDOWS, 4),
+///     "long",
+/// );
+/// ```
+pub fn escape_truncated(
+    p: &str,
+    esc: char,
+    mode: Mode,
+    max_chars: usize,
+) -> String {
+    truncate(p, esc, mode, max_chars, |s| s.chars().count())
+}
+
+/// Escape the given string in the same way as [`crate::escape_str`] and
+/// truncate the result so that it has at most `max_bytes` bytes when encoded
+/// as UTF-8.
+///
+/// This is useful for filesystems that limit the length of names in bytes
+/// (e.g. ext4). The truncation works in the same way as in
+/// [`escape_truncated`], so code points and escape sequences are never split
+/// and the extension is preserved if possible.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_truncated_bytes, Mode};
+///
+/// assert_eq!(
+///     escape_truncated_bytes("žluťoučký.txt", '_', Mode::ALL, 10),
+///     "žluť.txt",
+/// );
+/// ```
+pub fn escape_truncated_bytes(
+    p: &str,
+    esc: char,
+    mode: Mode,
+    max_bytes: usize,
+) -> String {
+    truncate(p, esc, mode, max_bytes, str::len)
+}
+
+/// Escape the given string in the same way as [`crate::escape_str`] and
+/// truncate the result so that it has at most `max_units` code units when
+/// encoded as UTF-16.
+///
+/// This is useful for filesystems that limit the length of names in UTF-16
+/// code units (e.g. NTFS and FAT with 255 units, see [`Mode::FAT`]). The
+/// truncation works in the same way as in [`escape_truncated`].
+///
+/// # Example
+/// ```
+/// use filesan::{escape_truncated_utf16, Mode};
+///
+/// assert_eq!(
+///     escape_truncated_utf16("😀😀😀.txt", '_', Mode::FAT, 8),
+///     "😀😀.txt",
+/// );
+/// ```
+pub fn escape_truncated_utf16(
+    p: &str,
+    esc: char,
+    mode: Mode,
+    max_units: usize,
+) -> String {
+    truncate(p, esc, mode, max_units, |s| s.encode_utf16().count())
+}
+
+/// Escape the given string in the same way as [`crate::escape_str`] and
+/// truncate the result so that it has at most `max_chars` characters. If the
+/// name is truncated, hash of the whole name is appended to the truncated
+/// stem, so that different long names with the same start give different
+/// results.
+///
+/// The truncated name ends with the escape character, `-` and 7 base36
+/// digits of 32 bit FNV-1a hash of the original name. The extension is
+/// preserved after the hash if possible. `-` and the digits are allowed with
+/// all modes and escaped names never contain the escape character followed
+/// by `-`, so truncated names are valid and they never collide with names
+/// that were not truncated.
+///
+/// If not even the hash fits, the name is truncated in the same way as in
+/// [`escape_truncated`].
+///
+/// # Example
+/// ```
+/// use filesan::{escape_truncated_unique, Mode};
+///
+/// let a = escape_truncated_unique("long name a.txt", '_', Mode::ALL, 14);
+/// let b = escape_truncated_unique("long name b.txt", '_', Mode::ALL, 14);
+/// assert_eq!(a.len(), 14);
+/// assert!(a.starts_with("l_-"));
+/// assert!(a.ends_with(".txt"));
+/// assert_ne!(a, b);
+///
+/// assert_eq!(
+///     escape_truncated_unique("short.txt", '_', Mode::ALL, 14),
+///     "short.txt"
+/// );
+/// ```
+pub fn escape_truncated_unique(
+    p: &str,
+    esc: char,
+    mode: Mode,
+    max_chars: usize,
+) -> String {
+    let len = |s: &str| s.chars().count();
+    let res = escape_str(p, esc, mode);
+    if len(&res) <= max_chars {
+        return res;
+    }
+
+    let hash = base36(fnv1a(p.as_bytes()));
+    let with_hash = |pre: &str, ext: &str| {
+        let mut marker = String::from(esc);
+        marker.push('-');
+        marker += &hash;
+        let hash_range = pre.len()..pre.len() + marker.len();
+
+        // Reserved names and trailing characters are decided by the whole
+        // result, but the marker itself is never escaped.
+        let name = String::from(pre) + &marker + ext;
+        let reserved = reserved_len(name.as_bytes(), mode);
+        let trailing = trailing_start(name.as_bytes(), mode);
+        let mut res = String::new();
+        for (idx, c) in name.char_indices() {
+            let pos = Position {
+                idx,
+                reserved,
+                trailing,
+            };
+            if !hash_range.contains(&idx)
+                && pos.reason(c == esc, allowed(c, mode)).is_some()
+            {
+                res += &EscapedChar::escaped(c, esc);
+            } else {
+                res.push(c);
+            }
+        }
+        res
+    };
+
+    let (stem, ext) = split_ext(p);
+    if !ext.is_empty()
+        && let Some(res) =
+            truncate_stem(stem, max_chars, len, |s| Some(with_hash(s, ext)))
+    {
+        return res;
+    }
+    truncate_stem(p, max_chars, len, |s| Some(with_hash(s, "")))
+        .unwrap_or_else(|| truncate(p, esc, mode, max_chars, len))
+}
+
+/// Escape the name and truncate it so that its length given by `len` is at
+/// most `max`.
+fn truncate(
+    p: &str,
+    esc: char,
+    mode: Mode,
+    max: usize,
+    len: impl Fn(&str) -> usize,
+) -> String {
+    let res = escape_str(p, esc, mode);
+    if len(&res) <= max {
+        return res;
+    }
+
+    // The prefix of the stem must not be empty.
+    let escape = |pre: &str, ext: &str| {
+        (!pre.is_empty()).then(|| {
+            let mut name = String::from(pre);
+            name += ext;
+            escape_str(&name, esc, mode)
+        })
+    };
+
+    let (stem, ext) = split_ext(p);
+    if !ext.is_empty()
+        && let Some(res) = truncate_stem(stem, max, &len, |s| escape(s, ext))
+    {
+        return res;
+    }
+    truncate_stem(p, max, &len, |s| escape(s, "")).unwrap_or_default()
+}
+
+/// Split the name to stem and extension. The extension contains the dot.
+pub(crate) fn split_ext(p: &str) -> (&str, &str) {
+    match p.rfind('.') {
+        Some(i) if i != 0 => p.split_at(i),
+        _ => (p, ""),
+    }
+}
+
+/// Find the longest prefix of `stem` so that the name created from the
+/// prefix by `build` fits to `max`. `build` may return [`None`] if the prefix
+/// is not valid.
+fn truncate_stem(
+    stem: &str,
+    max: usize,
+    len: impl Fn(&str) -> usize,
+    build: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    // The length of the result grows with the length of the prefix, so
+    // binary search can be used. The escaping of the prefix may be different
+    // at its end, so the result is not strictly monotonic and the found
+    // prefix is then shortened while the result doesn't fit.
+    let ends: Vec<_> = stem
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([stem.len()])
+        .collect();
+    let fits = |end: usize| build(&stem[..end]).filter(|r| len(r) <= max);
+    let cnt = ends.partition_point(|e| fits(*e).is_some() || *e == 0);
+    ends[..cnt.max(1)].iter().rev().find_map(|e| fits(*e))
+}
+
+/// 32 bit FNV-1a hash.
+fn fnv1a(data: &[u8]) -> u32 {
+    data.iter()
+        .fold(0x811C9DC5, |h, b| (h ^ *b as u32).wrapping_mul(0x01000193))
+}
+
+/// Format the number in base36 with exactly 7 lowercase digits.
+fn base36(mut n: u32) -> String {
+    let mut res = [b'0'; 7];
+    for d in res.iter_mut().rev() {
+        *d = b"0123456789abcdefghijklmnopqrstuvwxyz"[(n % 36) as usize];
+        n /= 36;
+    }
+    res.iter().map(|d| *d as char).collect()
+}
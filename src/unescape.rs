@@ -0,0 +1,273 @@
+use alloc::string::String;
+use core::{convert::Infallible, fmt::Display, iter::FusedIterator};
+
+/// Error returned by [`try_unescape_str`] when the string contains malformed
+/// escape sequence. Each variant contains the byte offset of the escape
+/// character that starts the malformed sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnescapeError {
+    /// The string ends before the escape sequence is complete.
+    TruncatedEscape(usize),
+    /// The escape character is not followed by the hex digits.
+    InvalidHexDigit(usize),
+    /// The escape sequence doesn't encode valid character.
+    InvalidCodePoint(usize),
+}
+
+impl UnescapeError {
+    /// Get the byte offset of the escape character that starts the malformed
+    /// sequence.
+    pub fn offset(&self) -> usize {
+        match self {
+            Self::TruncatedEscape(o)
+            | Self::InvalidHexDigit(o)
+            | Self::InvalidCodePoint(o) => *o,
+        }
+    }
+}
+
+impl Display for UnescapeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TruncatedEscape(o) => {
+                write!(f, "Truncated escape sequence at byte {o}.")
+            }
+            Self::InvalidHexDigit(o) => {
+                write!(f, "Invalid hex digit in escape sequence at byte {o}.")
+            }
+            Self::InvalidCodePoint(o) => {
+                write!(f, "Invalid character in escape sequence at byte {o}.")
+            }
+        }
+    }
+}
+
+impl core::error::Error for UnescapeError {}
+
+/// Reverse the escaping done by [`crate::escape_str`].
+///
+/// Every occurrence of the escape character followed by two hex digits, `u`
+/// and four hex digits or `U` and six hex digits is replaced by the character
+/// with the given value. Escape characters that are not followed by valid
+/// escape sequence are kept as they are. Use [`try_unescape_str`] if you want
+/// to detect such invalid sequences. See the [crate documentation](crate) for
+/// the exact format.
+///
+/// It is guaranteed that `unescape_str(&escape_str(s, esc, mode), esc) == s`
+/// for any string `s`, escape character `esc` and mode `mode`. Note that this
+/// doesn't hold for [`crate::replace_escape`] which is not reversible.
+///
+/// # Example
+/// ```
+/// use filesan::{escape_str, unescape_str, Mode};
+///
+/// assert_eq!(
+///     unescape_str("_00hello_2Fthe_5Fre.txt_3A_2E", '_'),
+///     "\x00hello/the_re.txt:."
+/// );
+///
+/// let name = "NUL.txt";
+/// let escaped = escape_str(name, '_', Mode::WINDOWS);
+/// assert_eq!(unescape_str(&escaped, '_'), name);
+/// ```
+pub fn unescape_str(s: &str, esc: char) -> String {
+    let res: Result<_, Infallible> = unescape_str_inner(s, esc, |res, _| {
+        res.push(esc);
+        Ok(false)
+    });
+    let Ok(res) = res;
+    res
+}
+
+/// Reverse the escaping done by [`crate::escape_str`].
+///
+/// Same as [`unescape_str`], but fail if the escape character is not followed
+/// by valid escape sequence. Such string could have never been produced by
+/// [`crate::escape_str`] with the same escape character.
+///
+/// # Errors
+/// - [`UnescapeError::TruncatedEscape`] if the string ends before the escape
+///   sequence is complete.
+/// - [`UnescapeError::InvalidHexDigit`] if the escape character is followed by
+///   character that is not hex digit.
+/// - [`UnescapeError::InvalidCodePoint`] if the escape sequence has value that
+///   is not valid character (e.g. surrogate).
+///
+/// # Example
+/// ```
+/// use filesan::{try_unescape_str, UnescapeError};
+///
+/// assert_eq!(try_unescape_str("_4EUL.txt", '_').unwrap(), "NUL.txt");
+/// assert_eq!(
+///     try_unescape_str("hello_", '_'),
+///     Err(UnescapeError::TruncatedEscape(5))
+/// );
+/// assert_eq!(
+///     try_unescape_str("my_name", '_'),
+///     Err(UnescapeError::InvalidHexDigit(2))
+/// );
+/// ```
+pub fn try_unescape_str(s: &str, esc: char) -> Result<String, UnescapeError> {
+    unescape_str_inner(s, esc, |_, e| Err(e))
+}
+
+/// Reverse the escaping done by [`crate::escape_str`] without failing.
+///
+/// Same as [`unescape_str`], but escape sequences with value that is not
+/// valid character (e.g. surrogate or value larger than `10FFFF`) are
+/// replaced with [`char::REPLACEMENT_CHARACTER`] (`U+FFFD`). Other malformed
+/// escape sequences are kept as they are. This is useful to display names
+/// that may not have been produced by [`crate::escape_str`].
+///
+/// # Example
+/// ```
+/// use filesan::unescape_lossy;
+///
+/// assert_eq!(unescape_lossy("a_2Fb", '_'), "a/b");
+/// assert_eq!(unescape_lossy("a_uD800b", '_'), "a\u{FFFD}b");
+/// assert_eq!(unescape_lossy("a_U110000_", '_'), "a\u{FFFD}_");
+/// assert_eq!(unescape_lossy("my_name", '_'), "my_name");
+/// ```
+pub fn unescape_lossy(s: &str, esc: char) -> String {
+    let res: Result<_, Infallible> = unescape_str_inner(s, esc, |res, e| {
+        if let UnescapeError::InvalidCodePoint(_) = e {
+            res.push(char::REPLACEMENT_CHARACTER);
+            Ok(true)
+        } else {
+            res.push(esc);
+            Ok(false)
+        }
+    });
+    let Ok(res) = res;
+    res
+}
+
+/// Iterator over the characters of unescaped string. See [`unescape_iter`].
+#[derive(Debug, Clone)]
+pub struct UnescapedChars<'a> {
+    s: &'a str,
+    /// The part of `s` that is not unescaped yet. It is empty after error.
+    rest: &'a str,
+    esc: char,
+}
+
+/// Unescape the given string lazily in the same way as
+/// [`try_unescape_str`]. Returns iterator over the characters of the
+/// unescaped string.
+///
+/// The iterator yields error for the first malformed escape sequence and
+/// then it ends. Collecting the iterator gives the same result as
+/// [`try_unescape_str`].
+///
+/// # Example
+/// ```
+/// use filesan::{unescape_iter, UnescapeError};
+///
+/// assert!(unescape_iter("a_2Fb_u017E", '_').eq("a/bž".chars().map(Ok)));
+/// let mut iter = unescape_iter("a_x", '_');
+/// assert_eq!(iter.next(), Some(Ok('a')));
+/// assert_eq!(iter.next(), Some(Err(UnescapeError::InvalidHexDigit(1))));
+/// assert_eq!(iter.next(), None);
+/// ```
+pub fn unescape_iter(s: &str, esc: char) -> UnescapedChars<'_> {
+    UnescapedChars { s, rest: s, esc }
+}
+
+impl Iterator for UnescapedChars<'_> {
+    type Item = Result<char, UnescapeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chars = self.rest.chars();
+        let c = chars.next()?;
+        let post = chars.as_str();
+        if c != self.esc {
+            self.rest = post;
+            return Some(Ok(c));
+        }
+
+        let offset = self.s.len() - self.rest.len();
+        match decode_hex(post, offset) {
+            Ok((c, len)) => {
+                self.rest = &post[len..];
+                Some(Ok(c))
+            }
+            Err(e) => {
+                self.rest = "";
+                Some(Err(e))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Malformed escape sequence ends the iterator, so only one item is
+        // guaranteed.
+        let rem = self.rest.len();
+        (usize::from(!self.rest.is_empty()), Some(rem))
+    }
+}
+
+impl FusedIterator for UnescapedChars<'_> {}
+
+/// Unescape the string. `invalid` is called for malformed escape sequences
+/// and it returns whether the whole sequence should be skipped. Otherwise
+/// only the escape character is skipped.
+fn unescape_str_inner<E>(
+    s: &str,
+    esc: char,
+    mut invalid: impl FnMut(&mut String, UnescapeError) -> Result<bool, E>,
+) -> Result<String, E> {
+    let mut res = String::new();
+    let mut rest = s;
+
+    while let Some((pre, post)) = rest.split_once(esc) {
+        res += pre;
+        let offset = s.len() - post.len() - esc.len_utf8();
+        match decode_hex(post, offset) {
+            Ok((c, len)) => {
+                res.push(c);
+                rest = &post[len..];
+            }
+            Err(e) => {
+                rest = if invalid(&mut res, e)? {
+                    let (marker, digits) = sequence_form(post);
+                    &post[marker + digits..]
+                } else {
+                    post
+                };
+            }
+        }
+    }
+
+    Ok(res + rest)
+}
+
+/// Decode the escape sequence at the start of `s` (after the escape
+/// character). Returns the character and the length of the sequence.
+pub(crate) fn decode_hex(
+    s: &str,
+    offset: usize,
+) -> Result<(char, usize), UnescapeError> {
+    let (marker, digits) = sequence_form(s);
+    let mut chars = s[marker..].chars();
+    let mut res = 0;
+    for _ in 0..digits {
+        let c = chars.next().ok_or(UnescapeError::TruncatedEscape(offset))?;
+        let d = c
+            .to_digit(16)
+            .ok_or(UnescapeError::InvalidHexDigit(offset))?;
+        res = res * 16 + d;
+    }
+    let c =
+        char::from_u32(res).ok_or(UnescapeError::InvalidCodePoint(offset))?;
+    Ok((c, marker + digits))
+}
+
+/// Get the length of the marker and the number of hex digits of the escape
+/// sequence at the start of `s` (after the escape character).
+fn sequence_form(s: &str) -> (usize, usize) {
+    match s.as_bytes().first() {
+        Some(b'u') => (1, 4),
+        Some(b'U') => (1, 6),
+        _ => (0, 2),
+    }
+}
@@ -0,0 +1,122 @@
+use alloc::vec::Vec;
+use core::{fmt::Display, ops::Deref};
+#[cfg(feature = "std")]
+use std::path::{Component, Path};
+
+use crate::{Issue, Mode, validate_issues};
+
+/// Name that is valid on the systems of its mode. It is checked with
+/// [`validate_issues`] when it is created, so it can be passed around without
+/// checking it again.
+///
+/// Empty name is accepted, because [`crate::escape_str`] leaves it empty and
+/// its result should always be valid name. Check [`str::is_empty`] if the
+/// name must not be empty.
+///
+/// [`TryFrom<&str>`] checks the name with [`Mode::SYSTEM`], because it can't
+/// take the mode and the name is most likely used on the current system. Use
+/// [`ValidName::new`] to check with other mode.
+///
+/// # Example
+/// ```
+/// use filesan::{Mode, ValidName};
+///
+/// let name = ValidName::new("report.txt", Mode::ALL).unwrap();
+/// assert_eq!(&*name, "report.txt");
+/// assert_eq!(name.mode(), Mode::ALL);
+/// assert!(name.ends_with(".txt"));
+///
+/// let issues = ValidName::new("NUL:", Mode::WINDOWS).unwrap_err();
+/// assert_eq!(issues.len(), 1);
+///
+/// assert!(ValidName::new("", Mode::ALL).is_ok());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ValidName<'a> {
+    name: &'a str,
+    mode: Mode,
+}
+
+impl<'a> ValidName<'a> {
+    /// Check that the name is valid with the given mode. Empty name is
+    /// always accepted.
+    ///
+    /// # Errors
+    /// - All the issues found by [`validate_issues`] if the name is not
+    ///   valid.
+    pub fn new(name: &'a str, mode: Mode) -> Result<Self, Vec<Issue>> {
+        if !name.is_empty() {
+            validate_issues(name, mode)?;
+        }
+        Ok(Self { name, mode })
+    }
+
+    /// Get the name.
+    pub fn as_str(&self) -> &'a str {
+        self.name
+    }
+
+    /// Get the mode with which the name was checked.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Get the name as single normal path component on the current target.
+    ///
+    /// Returns [`None`] if the name is not single normal component (e.g.
+    /// `a/b` or `..` are valid with [`Mode::NONE`]). The modes that contain
+    /// [`Mode::SYSTEM`] always give single normal component.
+    ///
+    /// # Example
+    /// ```
+    /// use std::path::Component;
+    /// use filesan::{Mode, ValidName};
+    ///
+    /// let name = ValidName::new("a.txt", Mode::SYSTEM).unwrap();
+    /// assert_eq!(
+    ///     name.as_path_component(),
+    ///     Some(Component::Normal("a.txt".as_ref()))
+    /// );
+    ///
+    /// let name = ValidName::new("..", Mode::NONE).unwrap();
+    /// assert_eq!(name.as_path_component(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn as_path_component(&self) -> Option<Component<'a>> {
+        let mut comps = Path::new(self.name).components();
+        match (comps.next(), comps.next()) {
+            (Some(Component::Normal(n)), None) if n == self.name => {
+                Some(Component::Normal(n))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Deref for ValidName<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.name
+    }
+}
+
+impl AsRef<str> for ValidName<'_> {
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+impl Display for ValidName<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ValidName<'a> {
+    type Error = Vec<Issue>;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Self::new(value, Mode::SYSTEM)
+    }
+}
@@ -0,0 +1,294 @@
+use alloc::vec::Vec;
+use core::{fmt::Display, ops::Range};
+
+use crate::{
+    Mode, allowed, invalid_chars, is_reserved, reserved_len, trailing_start,
+};
+
+/// Error returned by [`validate`]. Contains all the reasons why the name is
+/// not valid.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Disallowed characters and their byte offsets.
+    pub invalid: Vec<(usize, char)>,
+    /// `true` if the name is reserved.
+    pub reserved: bool,
+    /// `true` if the name ends with characters that are disallowed at the
+    /// end of the name.
+    pub trailing: bool,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Invalid name:")?;
+        let mut sep = " ";
+        for (i, c) in &self.invalid {
+            write!(f, "{sep}disallowed character {c:?} at {i}")?;
+            sep = ", ";
+        }
+        if self.reserved {
+            write!(f, "{sep}reserved name")?;
+            sep = ", ";
+        }
+        if self.trailing {
+            write!(f, "{sep}disallowed characters at the end")?;
+        }
+        f.write_str(".")
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+/// Checks whether the name may be used as it is on the given systems.
+///
+/// Unlike [`crate::needs_escape`], there is no escape character, so only the
+/// disallowed characters, reserved names and disallowed characters at the
+/// end are checked. All the problems are found in one pass.
+///
+/// # Errors
+/// - [`ValidationError`] if the name is not valid. It contains all the
+///   problems with the name.
+///
+/// # Example
+/// ```
+/// use filesan::{validate, Mode};
+///
+/// assert!(validate("hello.txt", Mode::ALL).is_ok());
+///
+/// let err = validate("NUL.a:b?.", Mode::WINDOWS).unwrap_err();
+/// assert_eq!(err.invalid, [(5, ':'), (7, '?')]);
+/// assert!(err.reserved);
+/// assert!(err.trailing);
+/// ```
+pub fn validate(s: &str, mode: Mode) -> Result<(), ValidationError> {
+    let err = ValidationError {
+        invalid: invalid_chars(s, mode).collect(),
+        reserved: is_reserved(s, mode),
+        trailing: trailing_start(s.as_bytes(), mode) != s.len(),
+    };
+
+    if err == ValidationError::default() {
+        Ok(())
+    } else {
+        Err(err)
+    }
+}
+
+/// Kind of problem with name. See [`Issue`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum IssueKind {
+    /// The character is disallowed.
+    Disallowed(char),
+    /// The name is reserved.
+    Reserved,
+    /// The characters are disallowed at the end of the name.
+    Trailing,
+    /// The name is empty.
+    Empty,
+}
+
+/// Names of the systems used in the messages of [`Issue`].
+const SYSTEM_NAMES: &[(Mode, &str)] = &[
+    (Mode::UNIX, "Unix"),
+    (Mode::WINDOWS, "Windows"),
+    (Mode::WINDOWS_END, "Windows"),
+    (Mode::MAC, "macOS"),
+    (Mode::POSIX, "POSIX"),
+    (Mode::ASCII_ONLY, "ASCII only"),
+    (Mode::FAT, "FAT"),
+    (Mode::HFS, "classic Mac OS"),
+    (Mode::CLOUD, "cloud storage"),
+];
+
+/// Problem with name found by [`validate_issues`] or
+/// [`validate_path_issues`].
+///
+/// The [`Display`] implementation gives human readable message (e.g.
+/// `character ':' at byte 7 is not allowed on Windows or macOS`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Issue {
+    /// Kind of the problem.
+    pub kind: IssueKind,
+    /// Byte range of the name with the problem. For reserved names it is the
+    /// reserved part of the name (e.g. `NUL` in `NUL.txt`) and for trailing
+    /// characters it is all the disallowed characters at the end.
+    pub span: Range<usize>,
+    /// The systems from the validated mode on which this is a problem. For
+    /// example `:` with [`Mode::ALL`] gives [`Mode::WINDOWS_MAC`].
+    pub systems: Mode,
+}
+
+impl Issue {
+    /// Create issue of the given kind in `name` and find the systems on which
+    /// it is a problem.
+    fn new(
+        kind: IssueKind,
+        span: Range<usize>,
+        name: &str,
+        mode: Mode,
+    ) -> Self {
+        let p = name.as_bytes();
+        let affects = |f: Mode| match kind {
+            IssueKind::Disallowed(c) => !allowed(c, f),
+            IssueKind::Reserved => reserved_len(p, f) != 0,
+            IssueKind::Trailing => trailing_start(p, f) != p.len(),
+            IssueKind::Empty => true,
+        };
+        let systems = SYSTEM_NAMES
+            .iter()
+            .map(|(f, _)| *f)
+            .filter(|f| mode.contains(*f) && affects(*f))
+            .collect();
+        Self {
+            kind,
+            span,
+            systems,
+        }
+    }
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let Range { start, end } = self.span;
+        match self.kind {
+            IssueKind::Disallowed(c) => {
+                write!(f, "character {c:?} at byte {start} is not allowed")?
+            }
+            IssueKind::Reserved => write!(f, "the name is reserved")?,
+            IssueKind::Trailing => write!(
+                f,
+                "characters at bytes {start}..{end} are not allowed at the \
+                 end of the name"
+            )?,
+            IssueKind::Empty => return write!(f, "the name is empty"),
+        }
+
+        let mut names = Vec::new();
+        for (s, n) in SYSTEM_NAMES {
+            if self.systems.contains(*s) && !names.contains(n) {
+                names.push(*n);
+            }
+        }
+        for (i, n) in names.iter().enumerate() {
+            let sep = match i {
+                0 => " on ",
+                _ if i + 1 == names.len() => " or ",
+                _ => ", ",
+            };
+            write!(f, "{sep}{n}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Find all the problems with the name on the given systems.
+///
+/// This checks the same things as [`validate`], but each problem is reported
+/// separately with its location and the systems on which it is a problem.
+/// Unlike [`validate`], empty name is also reported as problem, because it
+/// can't be used as file name.
+///
+/// The issues are ordered by the start of their spans. Characters that are
+/// both disallowed and at the end of the name are reported in both ways.
+/// Name without issues is not changed by [`crate::escape_str`] if it doesn't
+/// contain the escape character.
+///
+/// # Errors
+/// - All the issues with the name if there are any.
+///
+/// # Example
+/// ```
+/// use filesan::{validate_issues, IssueKind, Mode};
+///
+/// assert!(validate_issues("hello.txt", Mode::ALL).is_ok());
+///
+/// let issues = validate_issues("NUL.a:.", Mode::ALL).unwrap_err();
+/// let kinds: Vec<_> = issues.iter().map(|i| i.kind).collect();
+/// assert_eq!(
+///     kinds,
+///     [IssueKind::Reserved, IssueKind::Disallowed(':'), IssueKind::Trailing]
+/// );
+/// assert_eq!(issues[1].span, 5..6);
+/// assert_eq!(issues[1].systems, Mode::WINDOWS_MAC);
+/// assert_eq!(
+///     issues[1].to_string(),
+///     "character ':' at byte 5 is not allowed on Windows or macOS"
+/// );
+/// ```
+pub fn validate_issues(name: &str, mode: Mode) -> Result<(), Vec<Issue>> {
+    let issue = |kind, span| Issue::new(kind, span, name, mode);
+    if name.is_empty() {
+        return Err(alloc::vec![issue(IssueKind::Empty, 0..0)]);
+    }
+
+    let mut res = Vec::new();
+    let reserved = reserved_len(name.as_bytes(), mode);
+    if reserved != 0 {
+        res.push(issue(IssueKind::Reserved, 0..reserved));
+    }
+
+    res.extend(
+        invalid_chars(name, mode).map(|(i, c)| {
+            issue(IssueKind::Disallowed(c), i..i + c.len_utf8())
+        }),
+    );
+
+    let trailing = trailing_start(name.as_bytes(), mode);
+    if trailing != name.len() {
+        res.push(issue(IssueKind::Trailing, trailing..name.len()));
+    }
+
+    if res.is_empty() {
+        Ok(())
+    } else {
+        // Trailing characters may also be disallowed.
+        res.sort_by_key(|i| i.span.start);
+        Err(res)
+    }
+}
+
+/// Find all the problems with the components of the given relative path.
+///
+/// The path is split in the same way as in [`crate::escape_path`] and each
+/// component is checked with [`validate_issues`]. The spans of the issues
+/// are relative to the whole path. Empty components (e.g. in `a//b`) are
+/// ignored, but empty path is reported as [`IssueKind::Empty`].
+///
+/// # Errors
+/// - All the issues with all the components if there are any.
+///
+/// # Example
+/// ```
+/// use filesan::{validate_path_issues, IssueKind, Mode};
+///
+/// assert!(validate_path_issues("a/b.txt", Mode::ALL).is_ok());
+///
+/// let issues = validate_path_issues("a/b:c/NUL", Mode::WINDOWS).unwrap_err();
+/// assert_eq!(issues[0].kind, IssueKind::Disallowed(':'));
+/// assert_eq!(issues[0].span, 3..4);
+/// assert_eq!(issues[1].kind, IssueKind::Reserved);
+/// assert_eq!(issues[1].span, 6..9);
+/// ```
+pub fn validate_path_issues(p: &str, mode: Mode) -> Result<(), Vec<Issue>> {
+    if p.is_empty() {
+        return validate_issues(p, mode);
+    }
+
+    let windows = mode.contains(Mode::WINDOWS);
+    let mut res = Vec::new();
+    let mut start = 0;
+    for name in p.split(|c| c == '/' || (windows && c == '\\')) {
+        if let Err(issues) = validate_issues(name, mode)
+            && !name.is_empty()
+        {
+            res.extend(issues.into_iter().map(|mut i| {
+                i.span = i.span.start + start..i.span.end + start;
+                i
+            }));
+        }
+        // Separators are single byte.
+        start += name.len() + 1;
+    }
+
+    if res.is_empty() { Ok(()) } else { Err(res) }
+}
@@ -0,0 +1,95 @@
+use core::fmt::Display;
+
+use crate::{DISALLOWED_CHARS, Mode, allowed};
+
+/// Reason why character is disallowed. See [`why_disallowed`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DisallowedReason {
+    /// Control character (`0x00` - `0x1F`).
+    Control,
+    /// Path separator (`/`, `\` on windows and `:` on macos).
+    Separator,
+    /// Character with special meaning on windows (`<`, `>`, `:`, `"`, `|`,
+    /// `?`, `*`).
+    Metacharacter,
+    /// Character that is disallowed at the end of the name on windows (` `,
+    /// `.`).
+    Trailing,
+    /// Character that is not in the POSIX portable filename character set.
+    NotPortable,
+    /// Non ASCII character.
+    NonAscii,
+}
+
+impl Display for DisallowedReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Control => "control character",
+            Self::Separator => "path separator",
+            Self::Metacharacter => "Windows metacharacter",
+            Self::Trailing => "reserved trailing character",
+            Self::NotPortable => "character outside of the POSIX portable set",
+            Self::NonAscii => "non ASCII character",
+        })
+    }
+}
+
+/// Explain why the character is not allowed on the given systems. Returns
+/// [`None`] if the character is allowed (see [`allowed`]).
+///
+/// The second value is the set of flags from the mode that disallow the
+/// character (e.g. `:` with [`Mode::ALL`] gives [`Mode::WINDOWS_MAC`]).
+/// The reason is given by the system specific flags if they disallow the
+/// character. [`DisallowedReason::NotPortable`] and
+/// [`DisallowedReason::NonAscii`] are given only if the character is
+/// disallowed just by [`Mode::POSIX`] or [`Mode::ASCII_ONLY`].
+///
+/// # Example
+/// ```
+/// use filesan::{why_disallowed, DisallowedReason, Mode};
+///
+/// assert_eq!(
+///     why_disallowed(':', Mode::ALL),
+///     Some((DisallowedReason::Metacharacter, Mode::WINDOWS_MAC))
+/// );
+/// assert_eq!(
+///     why_disallowed(':', Mode::MAC),
+///     Some((DisallowedReason::Separator, Mode::MAC))
+/// );
+/// assert_eq!(why_disallowed(':', Mode::UNIX), None);
+/// assert_eq!(DisallowedReason::Control.to_string(), "control character");
+/// ```
+pub fn why_disallowed(
+    c: char,
+    mode: Mode,
+) -> Option<(DisallowedReason, Mode)> {
+    if allowed(c, mode) {
+        return None;
+    }
+
+    let table = DISALLOWED_CHARS
+        .get(c as usize)
+        .map_or(Mode::NONE, |m| m.intersection(mode));
+    let mut systems = table;
+    for f in [Mode::POSIX, Mode::ASCII_ONLY] {
+        if mode.contains(f) && !allowed(c, f) {
+            systems |= f;
+        }
+    }
+    if mode.contains(Mode::FAT) && systems.contains(Mode::WINDOWS) {
+        systems |= Mode::FAT;
+    }
+
+    let reason = match c {
+        _ if table.is_empty() && systems.contains(Mode::POSIX) => {
+            DisallowedReason::NotPortable
+        }
+        _ if table.is_empty() => DisallowedReason::NonAscii,
+        '\0'..='\x1F' => DisallowedReason::Control,
+        '/' | '\\' => DisallowedReason::Separator,
+        ':' if !table.contains(Mode::WINDOWS) => DisallowedReason::Separator,
+        ' ' | '.' => DisallowedReason::Trailing,
+        _ => DisallowedReason::Metacharacter,
+    };
+    Some((reason, systems))
+}
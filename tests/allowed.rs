@@ -0,0 +1,161 @@
+use filesan::{
+    Mode, allowed, allowed_str, allowed_u8, disallowed_chars, escape_str,
+    first_invalid, invalid_chars,
+};
+
+#[test]
+fn allowed_string() {
+    assert!(allowed_str("", Mode::ALL));
+    assert!(allowed_str("hello.txt", Mode::ALL));
+    assert!(allowed_str("žluťoučký kůň", Mode::ALL));
+    assert!(!allowed_str("žluť/kůň", Mode::UNIX));
+    assert!(!allowed_str("a:b", Mode::MAC));
+    assert!(allowed_str("a:b", Mode::UNIX));
+    assert!(allowed_str("NUL.", Mode::WINDOWS));
+    assert!(allowed_str("a b.", Mode::WINDOWS));
+    assert!(!allowed_str("a b.", Mode::WINDOWS_END));
+
+    // Boundary characters.
+    assert!(!allowed_str("a\x1F", Mode::WINDOWS));
+    assert!(allowed_str("a\x1F", Mode::UNIX));
+    assert!(allowed_str("a\x20", Mode::WINDOWS));
+    assert!(allowed_str("a\x7F", Mode::ALL));
+    assert!(allowed_str("a\u{80}", Mode::ALL));
+
+    for c in (0..0x100).filter_map(char::from_u32) {
+        for mode in (0..16).map(Mode::from_bits_retain) {
+            let s = format!("a{c}b");
+            assert_eq!(allowed_str(&s, mode), allowed(c, mode));
+        }
+    }
+}
+
+#[test]
+fn allowed_byte() {
+    for mode in (0..0x40).map(Mode::from_bits_retain) {
+        for b in 0..0x80 {
+            assert_eq!(allowed_u8(b, mode), allowed(b as char, mode));
+        }
+        let non_ascii = !mode.intersects(Mode::POSIX | Mode::ASCII_ONLY);
+        for b in 0x80..=0xFF {
+            assert_eq!(allowed_u8(b, mode), non_ascii);
+        }
+    }
+
+    const { assert!(!allowed_u8(b'/', Mode::UNIX)) };
+}
+
+#[test]
+fn invalid() {
+    let inv = |s, mode| invalid_chars(s, mode).collect::<Vec<_>>();
+
+    assert_eq!(inv("", Mode::ALL), []);
+    assert_eq!(inv("hello.txt", Mode::ALL), []);
+    assert_eq!(inv("NUL. _", Mode::WINDOWS), []);
+    assert_eq!(inv("a:b", Mode::UNIX), []);
+    assert_eq!(inv("a:b", Mode::MAC), [(1, ':')]);
+    assert_eq!(inv("ž:€/\u{10FFFF}\0", Mode::UNIX), [(6, '/'), (11, '\0')]);
+    assert_eq!(
+        inv("€<ž>\x1F\x20\x7F|", Mode::WINDOWS),
+        [(3, '<'), (6, '>'), (7, '\x1F'), (10, '|')]
+    );
+    assert_eq!(
+        invalid_chars("ž:a/b€", Mode::WINDOWS)
+            .rev()
+            .collect::<Vec<_>>(),
+        [(4, '/'), (2, ':')]
+    );
+
+    assert_eq!(first_invalid("", Mode::ALL), None);
+    assert_eq!(first_invalid("NUL. _", Mode::WINDOWS), None);
+    assert_eq!(first_invalid("ž€<>", Mode::WINDOWS), Some((5, '<')));
+    assert_eq!(first_invalid("a-ž", Mode::POSIX), Some((2, 'ž')));
+    for s in ["", "a:b/c", "\0\0", "ž€/"] {
+        assert_eq!(
+            first_invalid(s, Mode::ALL),
+            inv(s, Mode::ALL).first().copied()
+        );
+    }
+
+    let mut it = invalid_chars("/a/b/", Mode::UNIX);
+    assert_eq!(it.next(), Some((0, '/')));
+    assert_eq!(it.next_back(), Some((4, '/')));
+    assert_eq!(it.next(), Some((2, '/')));
+    assert_eq!(it.next_back(), None);
+
+    for mode in (0..16).map(Mode::from_bits_retain) {
+        let s = "žluť/ou:čký\0 kůň?.";
+        for (i, c) in invalid_chars(s, mode) {
+            assert_eq!(s[i..].chars().next(), Some(c));
+            assert!(!allowed(c, mode));
+        }
+        assert_eq!(
+            invalid_chars(s, mode).count(),
+            s.chars().filter(|c| !allowed(*c, mode)).count()
+        );
+    }
+}
+
+#[test]
+fn const_table() {
+    const fn table(mode: Mode) -> [bool; 128] {
+        let mut res = [false; 128];
+        let mut i = 0;
+        while i < res.len() {
+            res[i] = allowed(i as u8 as char, mode);
+            i += 1;
+        }
+        res
+    }
+
+    static TABLE: [bool; 128] = table(Mode::WINDOWS_MAC);
+    const UNIX_MAC: [bool; 128] = table(Mode::UNIX_MAC);
+
+    for c in 0..128u8 {
+        assert_eq!(TABLE[c as usize], allowed(c as char, Mode::WINDOWS_MAC));
+        assert_eq!(
+            UNIX_MAC[c as usize],
+            allowed(c as char, Mode::UNIX | Mode::MAC)
+        );
+    }
+    assert!(!TABLE[b':' as usize]);
+    assert!(!TABLE[b'\\' as usize]);
+    assert!(TABLE[b'a' as usize]);
+    assert!(!UNIX_MAC[b':' as usize]);
+    assert!(UNIX_MAC[b'\\' as usize]);
+
+    assert_eq!(Mode::UNIX_MAC, Mode::UNIX | Mode::MAC);
+    assert_eq!(Mode::UNIX_WINDOWS, Mode::UNIX | Mode::WINDOWS);
+    assert_eq!(Mode::WINDOWS_MAC, Mode::WINDOWS | Mode::MAC);
+}
+
+#[test]
+fn shift_in() {
+    // `\x0F` used to be disallowed on unix and mac like `\0`.
+    assert!(allowed('\x0F', Mode::UNIX));
+    assert!(allowed('\x0F', Mode::MAC));
+    assert!(!allowed('\x0F', Mode::WINDOWS));
+    assert_eq!(escape_str("\x0F", '_', Mode::UNIX), "\x0F");
+    assert_eq!(escape_str("\x0F", '_', Mode::MAC), "\x0F");
+    assert_eq!(escape_str("\x0F", '_', Mode::WINDOWS), "_0F");
+}
+
+#[test]
+fn disallowed() {
+    let chars = |mode| disallowed_chars(mode).collect::<String>();
+    assert_eq!(chars(Mode::NONE), "");
+    assert_eq!(chars(Mode::UNIX), "\0/");
+    assert_eq!(chars(Mode::MAC), "\0/:");
+    assert_eq!(chars(Mode::WINDOWS_END), " .");
+    let windows: String = ('\0'..' ').chain("\"*/:<>?\\|".chars()).collect();
+    assert_eq!(chars(Mode::WINDOWS), windows);
+
+    for mode in (0..0x40).map(Mode::from_bits_retain) {
+        for c in (0..0x80u8).map(char::from) {
+            assert_eq!(
+                disallowed_chars(mode).any(|d| d == c),
+                !allowed(c, mode)
+            );
+        }
+    }
+}
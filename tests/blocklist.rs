@@ -0,0 +1,79 @@
+use filesan::{ExtBlocklist, ExtPolicy, Mode, escape_str, needs_escape};
+
+const NAMES: &[&str] = &[
+    "setup.exe",
+    "setup.EXE",
+    "setup.Dll",
+    "setup.exe.txt",
+    "setup.txt.exe",
+    "setup.exe.lnk",
+    "set:up.exe",
+    "setup.ex:e",
+    "setup.e_xe",
+    "setup.exe.",
+    "setup.exe ",
+    "CON.exe",
+    ".exe",
+    "exe",
+    "",
+];
+
+#[test]
+fn blocklist() {
+    let rename = ExtBlocklist::new(["exe", ".dll", "LNK"], ExtPolicy::Rename);
+    let strip = ExtBlocklist::new(["exe", ".dll", "LNK"], ExtPolicy::Strip);
+    let error = ExtBlocklist::new(["exe", ".dll", "LNK"], ExtPolicy::Error);
+    assert_eq!(rename.policy(), ExtPolicy::Rename);
+
+    let esc = |l: &ExtBlocklist, s| l.escape(s, '_', Mode::WINDOWS);
+    assert_eq!(esc(&rename, "setup.exe").unwrap(), "setup.exe_");
+    assert_eq!(esc(&rename, "setup.EXE").unwrap(), "setup.EXE_");
+    assert_eq!(esc(&rename, "set:up.lnk").unwrap(), "set_3Aup.lnk_");
+    assert_eq!(esc(&rename, "CON.exe").unwrap(), "_43ON.exe_");
+    assert_eq!(esc(&strip, "setup.exe").unwrap(), "setup");
+    assert_eq!(esc(&strip, "setup.exe.dll").unwrap(), "setup");
+    assert_eq!(esc(&strip, "setup.txt.exe").unwrap(), "setup.txt");
+    assert_eq!(esc(&strip, "CON.exe").unwrap(), "_43ON");
+    assert_eq!(esc(&strip, "a .exe").unwrap(), "a_20");
+    let err = esc(&error, "setup.EXE").unwrap_err();
+    assert_eq!(err.extension(), "EXE");
+    assert_eq!(err.to_string(), "Forbidden extension `EXE`.");
+
+    // Extensions with escaped characters are not forbidden.
+    for name in [
+        "setup.exe.txt",
+        "setup.ex:e",
+        "setup.e_xe",
+        "setup.exe.",
+        ".exe",
+    ] {
+        for list in [&rename, &strip, &error] {
+            assert!(!list.is_forbidden(name));
+            assert_eq!(
+                esc(list, name).unwrap(),
+                escape_str(name, '_', Mode::WINDOWS)
+            );
+        }
+    }
+    assert_eq!(esc(&error, "setup.ex:e").unwrap(), "setup.ex_3Ae");
+    assert_eq!(esc(&error, "setup.e_xe").unwrap(), "setup.e_5Fxe");
+
+    for name in NAMES {
+        for mode in [Mode::UNIX, Mode::WINDOWS, Mode::ALL] {
+            for list in [&rename, &strip] {
+                let res = list.escape(name, '_', mode).unwrap();
+                let ext = res
+                    .rsplit_once('.')
+                    .filter(|(s, _)| !s.is_empty())
+                    .map_or("", |(_, e)| e);
+                assert!(
+                    !["exe", "dll", "lnk"]
+                        .iter()
+                        .any(|e| e.eq_ignore_ascii_case(ext))
+                );
+                let res = res.trim_end_matches('_');
+                assert!(!needs_escape(res, '~', mode), "{res:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use filesan::{Mode, allowed, escape_bytes, escape_str};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn bytes() {
+    assert_eq!(
+        escape_bytes(b"\x00hello/the_re.txt:.", b'_', Mode::UNIX),
+        b"_00hello_2Fthe_5Fre.txt:."
+    );
+    assert_eq!(
+        escape_bytes(b"a\xFF\x80.", b'_', Mode::WINDOWS),
+        b"a\xFF\x80_2E"
+    );
+    assert_eq!(
+        escape_bytes(b"nul.\xFF", b'_', Mode::WINDOWS),
+        b"_6Eul.\xFF"
+    );
+    assert_eq!(escape_bytes(b"\xFF", 0xFF, Mode::NONE), b"\xFF\x46\x46");
+    assert_eq!(escape_bytes(b"", b'_', Mode::ALL), b"");
+
+    for name in NAMES.iter().chain(&["žluťoučký kůň. ", "a\u{7F}b"]) {
+        // Non ASCII characters are escaped per byte.
+        for mode in modes().filter(|m| allowed('ž', *m)) {
+            assert_eq!(
+                escape_bytes(name.as_bytes(), b'_', mode),
+                escape_str(name, '_', mode).as_bytes()
+            );
+        }
+    }
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "std")]
+
+use filesan::{Mode, Sanitizer};
+
+#[test]
+fn cache() {
+    let base = Sanitizer::new().mode(Mode::WINDOWS);
+    let mut san = base.clone().cached(3);
+    assert_eq!(san.capacity(), 3);
+    assert!(san.is_empty());
+
+    assert_eq!(&*san.escape("a:"), "a_3A");
+    assert_eq!(&*san.escape("b."), "b_2E");
+    assert_eq!(&*san.escape("a:"), "a_3A");
+    assert_eq!(san.len(), 2);
+    let a = san.escape("a:");
+    let a2 = san.escape("a:");
+    assert!(std::sync::Arc::ptr_eq(&a, &a2));
+
+    // `b.` is the least recently used and it is removed.
+    san.escape("c");
+    san.escape("d");
+    assert_eq!(san.len(), 3);
+    assert!(std::sync::Arc::ptr_eq(&a, &san.escape("a:")));
+    assert_eq!(&*san.escape("b."), "b_2E");
+    assert_eq!(san.len(), 3);
+
+    san.clear();
+    assert!(san.is_empty());
+    assert_eq!(&*san.escape("NUL"), "_4EUL");
+
+    let mut none = base.clone().cached(0);
+    assert_eq!(&*none.escape("a:"), "a_3A");
+    assert!(none.is_empty());
+
+    // Pseudo random workload with repeats.
+    for cap in [1, 2, 5, 50] {
+        let mut san = base.clone().cached(cap);
+        let mut x = 7u32;
+        for _ in 0..2000 {
+            x = x.wrapping_mul(1103515245).wrapping_add(12345);
+            let name = format!("name:{}.", (x >> 16) % 20);
+            assert_eq!(*san.escape(&name), base.escape(&name));
+            assert!(san.len() <= cap);
+        }
+    }
+}
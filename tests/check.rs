@@ -0,0 +1,90 @@
+use filesan::{
+    Finding, IssueKind, Mode, Severity, WarningKind, check, escape_str,
+};
+
+mod common;
+
+use common::{NAMES, UNUSED_ESC, modes};
+
+/// Get the kinds of warnings in the report.
+fn warnings(name: &str, mode: Mode) -> Vec<WarningKind> {
+    check(name, mode)
+        .iter()
+        .filter_map(|f| match f {
+            Finding::Warning(w) => Some(w.kind),
+            Finding::Error(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn checks() {
+    let report = check("-rf ", Mode::UNIX);
+    assert!(!report.has_errors());
+    assert!(report.has_warnings());
+    assert_eq!(report.error_count(), 0);
+    assert_eq!(report.warning_count(), 2);
+    assert_eq!(
+        warnings("-rf ", Mode::UNIX),
+        [WarningKind::LeadingDash, WarningKind::TrailingSpaceOrDot]
+    );
+    let spans: Vec<_> = report.iter().map(Finding::span).collect();
+    assert_eq!(spans, [0..1, 3..4]);
+
+    let report = check("-rf ", Mode::ALL);
+    assert_eq!(report.error_count(), 1);
+    assert_eq!(warnings("-rf ", Mode::ALL), [WarningKind::LeadingDash]);
+    let first = report.iter().next().unwrap();
+    assert_eq!(first.severity(), Severity::Error);
+    assert!(
+        matches!(first, Finding::Error(i) if i.kind == IssueKind::Trailing)
+    );
+
+    // POSIX reserves names starting with `-`.
+    assert_eq!(warnings("-rf", Mode::POSIX), []);
+    assert_eq!(check("-rf", Mode::POSIX).error_count(), 1);
+
+    assert!(check("hello.txt", Mode::UNIX).is_empty());
+    assert_eq!(
+        warnings("a:b?", Mode::UNIX),
+        [
+            WarningKind::DisallowedElsewhere(':'),
+            WarningKind::DisallowedElsewhere('?'),
+        ]
+    );
+    assert_eq!(
+        warnings("a:b?", Mode::MAC),
+        [WarningKind::DisallowedElsewhere('?'),]
+    );
+    assert_eq!(
+        warnings("nul.txt", Mode::UNIX),
+        [WarningKind::ReservedElsewhere,]
+    );
+    assert_eq!(warnings("  a", Mode::UNIX), [WarningKind::LeadingSpace]);
+    assert_eq!(warnings("a.", Mode::MAC), [WarningKind::TrailingSpaceOrDot]);
+    assert_eq!(warnings(&"a".repeat(200), Mode::UNIX), []);
+    assert_eq!(
+        warnings(&"a".repeat(201), Mode::UNIX),
+        [WarningKind::TooLong]
+    );
+
+    for name in NAMES {
+        for mode in modes() {
+            let report = check(name, mode);
+            if !name.is_empty() {
+                assert_eq!(
+                    report.has_errors(),
+                    escape_str(name, UNUSED_ESC, mode) != *name,
+                    "{name:?} {mode}"
+                );
+            }
+            assert_eq!(
+                report.error_count() + report.warning_count(),
+                report.iter().count()
+            );
+            let severities: Vec<_> =
+                (&report).into_iter().map(Finding::severity).collect();
+            assert!(severities.is_sorted_by(|a, b| a >= b));
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use filesan::{
+    EscapeError, Mode, allowed, escape_str, is_reserved, try_escape_str,
+};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn try_escape() {
+    let err = |c| Err(EscapeError::InvalidEscapeChar(c));
+
+    assert_eq!(try_escape_str("a/b", '/', Mode::UNIX), err('/'));
+    assert_eq!(try_escape_str("a/b", '/', Mode::NONE).unwrap(), "a/2Fb");
+    assert_eq!(try_escape_str("a:b", ':', Mode::MAC), err(':'));
+    assert_eq!(try_escape_str("a:b", ':', Mode::UNIX).unwrap(), "a:3Ab");
+    assert_eq!(try_escape_str("a", '\0', Mode::UNIX), err('\0'));
+    assert_eq!(try_escape_str("a", '0', Mode::NONE), err('0'));
+    assert_eq!(try_escape_str("a", 'a', Mode::NONE), err('a'));
+    assert_eq!(try_escape_str("a", 'F', Mode::NONE), err('F'));
+    assert_eq!(try_escape_str("ag", 'g', Mode::NONE).unwrap(), "ag67");
+    assert_eq!(try_escape_str("-a", '-', Mode::POSIX), err('-'));
+    assert_eq!(try_escape_str("-a", '-', Mode::UNIX).unwrap(), "-2Da");
+    assert_eq!(try_escape_str(" a", ' ', Mode::CLOUD), err(' '));
+    assert_eq!(try_escape_str(" a", ' ', Mode::WINDOWS).unwrap(), " 20a");
+    assert_eq!(try_escape_str("a", '.', Mode::UNIX), err('.'));
+    assert_eq!(
+        EscapeError::InvalidEscapeChar('/').to_string(),
+        "Invalid escape character '/'."
+    );
+
+    for name in NAMES {
+        for mode in modes() {
+            for esc in ['_', '~', '€'] {
+                let res = try_escape_str(name, esc, mode);
+                if allowed(esc, mode) && !is_reserved(&esc.to_string(), mode) {
+                    assert_eq!(res.unwrap(), escape_str(name, esc, mode));
+                } else {
+                    assert_eq!(res, err(esc));
+                }
+            }
+        }
+    }
+}
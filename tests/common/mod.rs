@@ -0,0 +1,101 @@
+//! Test data shared by the integration tests.
+
+// Each test crate uses only some of the items.
+#![allow(dead_code)]
+
+use filesan::Mode;
+
+/// Names that cover the special cases of all the modes: disallowed and
+/// trailing characters, reserved names and prefixes, existing escape
+/// sequences and non ASCII characters.
+pub const NAMES: &[&str] = &[
+    "",
+    "hello.txt",
+    "\x00hello/the_re.txt:.",
+    ".",
+    "..",
+    "...",
+    "...txt",
+    ".bashrc",
+    "report.",
+    "report. . ",
+    "archive.tar.gz",
+    "song.mp3",
+    "a: b .mp3",
+    "CON.flac",
+    ".mp3",
+    "a. .mp3",
+    "a_b.m_p3",
+    "a.mp3 ",
+    "a.ž",
+    "it's \"quoted\"\n",
+    "a ",
+    "a.",
+    "a. ",
+    " a",
+    " a ",
+    "a  .  b",
+    "a:b",
+    "a::b",
+    "a:b?.txt",
+    "a:b/c",
+    "a/b.c:d",
+    "a<b>c\"d|e?f*g\\h",
+    "a\\b",
+    "::::.::",
+    "NUL",
+    "nul.txt",
+    "NUL .",
+    "NUL.tar.gz",
+    "con.tar.gz",
+    "nulnul.txt",
+    "CON",
+    "con.txt",
+    "con .txt",
+    "cons",
+    "COM1.",
+    "COM1. ",
+    "COM¹",
+    "COM¹.a",
+    "CONOUT$.x",
+    "lpt9.tar.gz",
+    "-a",
+    "-rf ",
+    "--",
+    "- .",
+    "a-",
+    "~$a",
+    "~$a.docx",
+    "~a",
+    ".lock",
+    ".LOCK",
+    "a.lock",
+    "Desktop.ini",
+    "_",
+    "_5F",
+    "__2F",
+    "_4EUL",
+    "a%b%2F",
+    "a\\b\\u{2F}",
+    "žluťoučký kůň.txt",
+    "žluťoučký kůň.",
+    "Žluťoučký kůň.txt",
+    "žluť.ogg",
+    "Ñul",
+    "łódź. ",
+    "e\u{301}\u{302}",
+    "漢字/かな",
+    "☃:😀",
+    "Œuvre – “draft”…",
+    "€€€€.€€",
+    "😀/€",
+    "😀/.",
+];
+
+/// All the combinations of the mode flags.
+pub fn modes() -> impl Iterator<Item = Mode> {
+    (0..0x200).map(Mode::from_bits_retain)
+}
+
+/// Escape character that is not in any of the [`NAMES`].
+pub const UNUSED_ESC: char = '\u{10FFFF}';
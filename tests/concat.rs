@@ -0,0 +1,60 @@
+use filesan::{Mode, escape_str, finalize, push_escaped, unescape_str};
+
+mod common;
+
+use common::modes;
+
+fn join(fragments: &[&str], esc: char, mode: Mode) -> String {
+    let mut res = String::new();
+    for f in fragments {
+        push_escaped(&mut res, f, esc, mode);
+    }
+    finalize(&mut res, esc, mode);
+    res
+}
+
+#[test]
+fn concat() {
+    assert_eq!(join(&["CON", ".ext"], '_', Mode::WINDOWS), "_43ON.ext");
+    assert_eq!(join(&["CO", "N"], '_', Mode::WINDOWS), "_43ON");
+    assert_eq!(join(&["CON", "x"], '_', Mode::WINDOWS), "CONx");
+    assert_eq!(join(&["a", " "], '_', Mode::WINDOWS), "a_20");
+    assert_eq!(join(&["a ", ""], '_', Mode::WINDOWS), "a_20");
+    assert_eq!(join(&["a ", "b"], '_', Mode::WINDOWS), "a b");
+    assert_eq!(join(&["a.", " ."], '_', Mode::WINDOWS), "a_2E_20_2E");
+    assert_eq!(join(&["a", "/", "b"], '_', Mode::UNIX), "a_2Fb");
+    assert_eq!(join(&["_", "x"], '_', Mode::UNIX), "_5Fx");
+    assert_eq!(join(&[".", "."], '_', Mode::ALL), "_2E_2E");
+    assert_eq!(join(&[".", "."], '_', Mode::UNIX), "_2E.");
+    assert_eq!(join(&["-", "rf"], '-', Mode::POSIX), "-2Drf");
+    assert_eq!(join(&[], '_', Mode::ALL), "");
+
+    let mut name = String::from("a");
+    finalize(&mut name, '_', Mode::WINDOWS);
+    push_escaped(&mut name, "b.", '_', Mode::WINDOWS);
+    finalize(&mut name, '_', Mode::WINDOWS);
+    finalize(&mut name, '_', Mode::WINDOWS);
+    assert_eq!(name, "ab_2E");
+
+    const ALPHABET: &[&str] = &[
+        "", "a", ".", " ", "_", "CO", "N", "nul", "1", "/", ":", "ž", "-",
+    ];
+    let mut x = 1u32;
+    for _ in 0..5000 {
+        let mut fragments = vec![];
+        x = x.wrapping_mul(1103515245).wrapping_add(12345);
+        for _ in 0..(x >> 16) % 5 {
+            x = x.wrapping_mul(1103515245).wrapping_add(12345);
+            fragments.push(ALPHABET[(x >> 16) as usize % ALPHABET.len()]);
+        }
+        let name = fragments.concat();
+        for mode in modes() {
+            for esc in ['_', '~', '-', ' '] {
+                let res = join(&fragments, esc, mode);
+                assert_eq!(unescape_str(&res, esc), name);
+                let expected = escape_str(&name, esc, mode);
+                assert_eq!(res, expected, "{fragments:?}");
+            }
+        }
+    }
+}
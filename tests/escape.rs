@@ -1,4 +1,19 @@
-use filesan::{Mode, escape_str, replace_escape};
+use std::{borrow::Cow, collections::HashSet};
+
+use filesan::{
+    DEFAULT_ESC, HexCase, Mode, allowed, escape, escape_all, escape_chars,
+    escape_portable, escape_str, escape_str_auto, escape_str_cased,
+    escape_str_cow, escape_str_idempotent, escape_str_into, escape_str_or,
+    escape_str_prefixed, escape_str_raw, escape_with, escaped_len,
+    is_reserved, needs_escape, replace_escape, unescape_str, validate_issues,
+};
+
+mod common;
+
+use common::{NAMES, UNUSED_ESC, modes};
+
+const MODES: &[Mode] =
+    &[Mode::NONE, Mode::UNIX, Mode::WINDOWS, Mode::MAC, Mode::ALL];
 
 #[test]
 fn esc() {
@@ -20,7 +35,7 @@ fn esc() {
     );
 
     assert_eq!(escape_str("..", '_', Mode::UNIX), "_2E.");
-    assert_eq!(escape_str("..", '_', Mode::WINDOWS), "._2E");
+    assert_eq!(escape_str("..", '_', Mode::WINDOWS), "_2E_2E");
     assert_eq!(escape_str("..", '_', Mode::MAC), "_2E.");
     assert_eq!(escape_str("..", '_', Mode::UNIX | Mode::WINDOWS), "_2E_2E");
 
@@ -35,6 +50,28 @@ fn esc() {
     assert_eq!(escape_str("NUL.txt", '_', Mode::UNIX), "NUL.txt");
     assert_eq!(escape_str("NUL.txt", '_', Mode::WINDOWS), "_4EUL.txt");
     assert_eq!(escape_str("NUL.txt", '_', Mode::MAC), "NUL.txt");
+
+    assert_eq!(escape_str("nul", '_', Mode::WINDOWS), "_6Eul");
+    assert_eq!(escape_str("Nul.txt", '_', Mode::WINDOWS), "_4Eul.txt");
+    assert_eq!(escape_str("CoM1", '_', Mode::WINDOWS), "_43oM1");
+    assert_eq!(escape_str("lpt1.txt", '_', Mode::UNIX), "lpt1.txt");
+
+    assert_eq!(
+        escape_str("NUL.txt.bak", '_', Mode::WINDOWS),
+        "_4EUL.txt.bak"
+    );
+    assert_eq!(escape_str("COM1.a.b.c", '_', Mode::WINDOWS), "_43OM1.a.b.c");
+    assert_eq!(escape_str("CON.tar.gz", '_', Mode::ALL), "_43ON.tar.gz");
+    assert_eq!(escape_str("CON.tar.gz", '_', Mode::UNIX), "CON.tar.gz");
+    assert_eq!(escape_str("CON", '_', Mode::WINDOWS), "_43ON");
+    assert_eq!(escape_str("CONtar.gz", '_', Mode::WINDOWS), "CONtar.gz");
+    assert_eq!(escape_str("CON..", '_', Mode::WINDOWS), "_43ON_2E_2E");
+
+    assert_eq!(escape_str("file...", '_', Mode::WINDOWS), "file_2E_2E_2E");
+    assert_eq!(escape_str("name. ", '_', Mode::WINDOWS), "name_2E_20");
+    assert_eq!(escape_str("a. .b. ", '_', Mode::WINDOWS), "a. .b_2E_20");
+    assert_eq!(escape_str("file...", '_', Mode::UNIX), "file...");
+    assert_eq!(replace_escape("name. ", '_', Mode::WINDOWS), "name__");
 }
 
 #[test]
@@ -61,7 +98,7 @@ fn replace() {
     );
 
     assert_eq!(replace_escape("..", '_', Mode::UNIX), "_.");
-    assert_eq!(replace_escape("..", '_', Mode::WINDOWS), "._");
+    assert_eq!(replace_escape("..", '_', Mode::WINDOWS), "__");
     assert_eq!(replace_escape("..", '_', Mode::MAC), "_.");
     assert_eq!(replace_escape("..", '_', Mode::UNIX | Mode::WINDOWS), "__");
 
@@ -77,3 +114,558 @@ fn replace() {
     assert_eq!(replace_escape("NUL.txt", '_', Mode::WINDOWS), "_UL.txt");
     assert_eq!(replace_escape("NUL.txt", '_', Mode::MAC), "NUL.txt");
 }
+
+#[test]
+fn cow() {
+    for mode in modes() {
+        for name in NAMES {
+            let res = escape_str_cow(name, '_', mode);
+            assert_eq!(res, escape_str(name, '_', mode));
+            assert_eq!(matches!(res, Cow::Borrowed(_)), res == *name);
+        }
+    }
+
+    assert!(matches!(
+        escape_str_cow("hello.txt", '_', Mode::ALL),
+        Cow::Borrowed("hello.txt")
+    ));
+    assert!(matches!(
+        escape_str_cow("hello_.txt", '_', Mode::NONE),
+        Cow::Owned(_)
+    ));
+
+    let batch = ["song.mp3", "photo 01.jpg", "CON.txt", "a:b", "notes"];
+    let owned: Vec<_> = batch
+        .iter()
+        .filter(|n| {
+            matches!(escape_str_cow(n, '_', Mode::WINDOWS), Cow::Owned(_))
+        })
+        .collect();
+    assert_eq!(owned, [&"CON.txt", &"a:b"]);
+}
+
+#[test]
+fn fallback() {
+    assert_eq!(escape_str_or("", '_', Mode::ALL, "_"), "_");
+    assert_eq!(escape_str_or("", '_', Mode::ALL, "file"), "file");
+    assert_eq!(escape_str_or("_", '_', Mode::ALL, "_"), "_5F");
+
+    let mut results = HashSet::new();
+    for mode in modes() {
+        for name in NAMES {
+            let res = escape_str_or(name, '_', mode, "_");
+            assert!(!res.is_empty());
+            if !name.is_empty() {
+                assert_eq!(res, escape_str(name, '_', mode));
+            }
+            if mode == Mode::ALL {
+                assert!(results.insert(res));
+            }
+        }
+    }
+}
+
+#[test]
+fn needs() {
+    // Escape character.
+    assert!(needs_escape("a_b", '_', Mode::NONE));
+    assert!(!needs_escape("a_b", '~', Mode::NONE));
+    // Disallowed character.
+    assert!(needs_escape("a/b", '_', Mode::UNIX));
+    assert!(needs_escape("a:b", '_', Mode::MAC));
+    assert!(!needs_escape("a:b", '_', Mode::UNIX));
+    // Disallowed trailing character.
+    assert!(needs_escape("ab.", '_', Mode::WINDOWS));
+    assert!(needs_escape("ab ", '_', Mode::WINDOWS));
+    assert!(!needs_escape("ab.", '_', Mode::UNIX | Mode::MAC));
+    // Reserved name.
+    assert!(needs_escape("..", '_', Mode::UNIX));
+    assert!(needs_escape("aux", '_', Mode::WINDOWS));
+    assert!(needs_escape("Aux.txt", '_', Mode::WINDOWS));
+    assert!(!needs_escape("aux.txt", '_', Mode::UNIX));
+
+    for mode in modes() {
+        for name in NAMES {
+            assert_eq!(
+                needs_escape(name, '_', mode),
+                escape_str(name, '_', mode) != *name
+            );
+        }
+    }
+
+    // Compare on all short strings made of the interesting characters.
+    const ALPHABET: &[char] = &[
+        'a', '.', ' ', '_', 'N', 'u', 'L', 'C', 'o', 'M', '1', '/', ':', 'ž',
+    ];
+    let mut names = vec![String::new()];
+    for _ in 0..4 {
+        names = names
+            .iter()
+            .flat_map(|n| ALPHABET.iter().map(move |c| format!("{n}{c}")))
+            .collect();
+        for mode in modes() {
+            for name in &names {
+                assert_eq!(
+                    needs_escape(name, '_', mode),
+                    escape_str(name, '_', mode) != *name,
+                    "{name:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn into() {
+    let mut res = String::new();
+    for mode in modes() {
+        for name in NAMES {
+            res.clear();
+            escape_str_into(name, '_', mode, &mut res);
+            assert_eq!(res, escape_str(name, '_', mode));
+        }
+    }
+
+    let mut res = "NUL/".to_string();
+    escape_str_into("NUL", '_', Mode::WINDOWS, &mut res);
+    escape_str_into(".", '_', Mode::WINDOWS, &mut res);
+    assert_eq!(res, "NUL/_4EUL_2E");
+}
+
+#[test]
+fn len() {
+    for mode in modes() {
+        for name in NAMES.iter().chain(&["€", "a€b.", "\u{10FFFF}:", "NUL€"])
+        {
+            for esc in ['_', 'é', '€', '\u{10FFFF}'] {
+                assert_eq!(
+                    escaped_len(name, esc, mode),
+                    escape_str(name, esc, mode).len(),
+                    "{name:?} {esc:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn default() {
+    assert_eq!(DEFAULT_ESC, '_');
+    for name in NAMES {
+        assert_eq!(escape(name), escape_str(name, '_', Mode::SYSTEM));
+        assert_eq!(escape_all(name), escape_str(name, '_', Mode::ALL));
+    }
+
+    assert_eq!(
+        escape_all("\x00hello/the_re.txt:."),
+        "_00hello_2Fthe_5Fre.txt_3A_2E"
+    );
+    assert_eq!(escape_all(".."), "_2E_2E");
+    assert_eq!(escape_all("nul.txt"), "_6Eul.txt");
+}
+
+#[test]
+#[cfg(all(unix, not(target_os = "macos")))]
+fn default_unix() {
+    assert_eq!(
+        escape("\x00hello/the_re.txt:."),
+        "_00hello_2Fthe_5Fre.txt:."
+    );
+    assert_eq!(escape(".."), "_2E.");
+    assert_eq!(escape("NUL.txt"), "NUL.txt");
+}
+
+#[test]
+#[cfg(windows)]
+fn default_windows() {
+    assert_eq!(
+        escape("\x00hello/the_re.txt:."),
+        "_00hello_2Fthe_5Fre.txt_3A_2E"
+    );
+    assert_eq!(escape(".."), "_2E_2E");
+    assert_eq!(escape("NUL.txt"), "_4EUL.txt");
+}
+
+#[test]
+#[cfg(target_os = "macos")]
+fn default_mac() {
+    assert_eq!(
+        escape("\x00hello/the_re.txt:."),
+        "_00hello_2Fthe_5Fre.txt_3A."
+    );
+    assert_eq!(escape(".."), "_2E.");
+    assert_eq!(escape("NUL.txt"), "NUL.txt");
+}
+
+#[test]
+fn all() {
+    assert_eq!(escape_str("a ", '_', Mode::ALL), "a_20");
+    assert_eq!(escape_str("a.. ", '_', Mode::ALL), "a_2E_2E_20");
+    assert_eq!(escape_str("a b.c", '_', Mode::ALL), "a b.c");
+    assert_eq!(
+        escape_str("a b.c", '_', Mode::ALL | Mode::WINDOWS_END),
+        "a_20b_2Ec"
+    );
+}
+
+#[test]
+fn portable() {
+    assert_eq!(escape_portable("aux ", '_'), "aux_20");
+    assert_eq!(escape_portable("report.", '_'), "report_2E");
+    assert_eq!(escape_portable(".", '_'), "_2E");
+    assert_eq!(escape_portable("a<>b", '_'), "a_3C_3Eb");
+
+    for name in NAMES.iter().chain(&["aux ", "report.", "?", "NUL", "."]) {
+        let res = escape_portable(name, '_');
+        for mode in [Mode::UNIX, Mode::WINDOWS, Mode::MAC, Mode::ALL] {
+            assert_eq!(escape_str(&res, UNUSED_ESC, mode), res, "{name:?}");
+        }
+    }
+}
+
+#[test]
+fn with() {
+    assert_eq!(
+        escape_with("a#b_c d", '_', |c| c != '#' && c != ' '),
+        "a_23b_5Fc_20d"
+    );
+    assert_eq!(escape_with("NUL. ", '_', |_| true), "NUL. ");
+    assert_eq!(escape_with("€a", '_', |c| c.is_ascii()), "_u20ACa");
+
+    for name in NAMES {
+        assert_eq!(
+            escape_with(name, '_', |c| filesan::allowed(c, Mode::MAC)),
+            escape_with(name, '_', |c| c != '\0' && c != '/' && c != ':'),
+        );
+    }
+}
+
+#[test]
+fn prefixed() {
+    let esc = |s| escape_str_prefixed(s, '_', '~', Mode::WINDOWS);
+    assert_eq!(esc("NUL"), "~NUL");
+    assert_eq!(esc("~NUL"), "_7ENUL");
+    assert_ne!(esc("NUL"), esc("~NUL"));
+    assert_eq!(esc("com1.txt."), "~com1.txt_2E");
+    assert_eq!(esc("a~_:"), "a_7E_5F_3A");
+    assert_eq!(escape_str_prefixed(".", '_', '~', Mode::UNIX), "~.");
+    assert_eq!(escape_str_prefixed(".", '_', '~', Mode::ALL), "~_2E");
+    let unix_end = Mode::UNIX | Mode::WINDOWS_END;
+    assert_eq!(escape_str_prefixed("..", '_', '~', unix_end), "~_2E_2E");
+    let cloud = |s| escape_str_prefixed(s, '_', '~', Mode::CLOUD);
+    assert_eq!(cloud("~$a"), "~_7E$a");
+    assert_eq!(cloud(" "), "~_20");
+
+    // The results are valid if the prefix is allowed.
+    for name in NAMES.iter().filter(|n| !n.is_empty()) {
+        for mode in modes().filter(|m| allowed('~', *m)) {
+            let res = escape_str_prefixed(name, '_', '~', mode);
+            assert!(validate_issues(&res, mode).is_ok(), "{res:?} {mode}");
+        }
+    }
+
+    // Without reserved names it is the same as escaping also the prefix.
+    for name in NAMES {
+        for mode in modes() {
+            if is_reserved(name, mode) {
+                continue;
+            }
+            assert_eq!(
+                escape_str_prefixed(name, '_', '~', mode),
+                escape_str(name, '_', mode).replace('~', "_7E"),
+            );
+        }
+    }
+
+    // The results are unique and can be reversed.
+    const ALPHABET: &[char] = &['~', '_', 'n', 'N', 'u', 'l', '.', ' '];
+    let mut names = vec![String::new()];
+    for _ in 0..4 {
+        names = names
+            .iter()
+            .flat_map(|n| ALPHABET.iter().map(move |c| format!("{n}{c}")))
+            .collect();
+        for mode in MODES {
+            let mut res = HashSet::new();
+            for name in &names {
+                let e = escape_str_prefixed(name, '_', '~', *mode);
+                let u = unescape_str(e.strip_prefix('~').unwrap_or(&e), '_');
+                assert_eq!(&u, name);
+                assert!(res.insert(e));
+            }
+        }
+    }
+}
+
+#[test]
+fn auto() {
+    let name = "my_long_file_name_with__underscores";
+    let (res, esc) = escape_str_auto(name, &['_', '-', '~'], Mode::ALL);
+    assert_eq!(res, name);
+    assert_eq!(esc, '-');
+
+    let name = "my_file-name";
+    let (res, esc) = escape_str_auto(name, &['_', '-', '~'], Mode::ALL);
+    assert_eq!(res, name);
+    assert_eq!(esc, '~');
+
+    assert_eq!(
+        escape_str_auto("a_b-c:", &['_', '-'], Mode::WINDOWS),
+        ("a_5Fb-c_3A".to_string(), '_')
+    );
+    assert_eq!(
+        escape_str_auto("a_b:", &[], Mode::WINDOWS),
+        ("a_5Fb_3A".to_string(), DEFAULT_ESC)
+    );
+
+    for name in NAMES {
+        for mode in modes() {
+            let (res, esc) = escape_str_auto(name, &['_', '~', '€'], mode);
+            assert_eq!(res, escape_str(name, esc, mode));
+            assert_eq!(unescape_str(&res, esc), *name);
+        }
+    }
+}
+
+#[test]
+fn fat() {
+    assert!(Mode::FAT.contains(Mode::WINDOWS));
+    assert!(!Mode::ALL.contains(Mode::FAT));
+    for name in NAMES.iter().chain(&["CON", "lpt1.txt", "a<b>", "a. "]) {
+        assert_eq!(
+            escape_str(name, '_', Mode::FAT),
+            escape_str(name, '_', Mode::WINDOWS)
+        );
+        assert_eq!(
+            is_reserved(name, Mode::FAT),
+            is_reserved(name, Mode::WINDOWS)
+        );
+    }
+    for c in "\"*/:<>?\\|\x00\x1f".chars() {
+        assert!(!filesan::allowed(c, Mode::FAT));
+    }
+    assert_eq!(escape_str("NUL. ", '_', Mode::FAT), "_4EUL_2E_20");
+}
+
+#[test]
+fn hfs() {
+    let esc = |s| escape_str(s, '_', Mode::HFS);
+    assert_eq!(esc("a/b"), "a/b");
+    assert_eq!(esc("a:b\0"), "a_3Ab_00");
+    assert_eq!(esc("a\\b|c. "), "a\\b|c. ");
+    assert_eq!(esc("."), ".");
+    assert_eq!(esc(".."), "..");
+    assert_eq!(esc("NUL"), "NUL");
+    assert!(!is_reserved("..", Mode::HFS));
+    assert!(filesan::allowed('/', Mode::HFS));
+    assert!(!filesan::allowed('/', Mode::MAC));
+    assert!(!filesan::allowed(':', Mode::HFS));
+    assert!(!Mode::ALL.contains(Mode::HFS));
+}
+
+#[test]
+fn cloud() {
+    let esc = |s| escape_str(s, '_', Mode::CLOUD);
+    assert!(Mode::CLOUD.contains(Mode::WINDOWS));
+    assert!(!Mode::ALL.contains(Mode::CLOUD));
+    assert_eq!(esc(" a "), "_20a_20");
+    assert_eq!(esc("~$report.docx"), "_7E$report.docx");
+    assert_eq!(esc("~report.docx"), "~report.docx");
+    assert_eq!(esc(".lock"), "_2Elock");
+    assert_eq!(esc("Desktop.ini"), "_44esktop.ini");
+    assert_eq!(esc("a:b"), "a_3Ab");
+    assert_eq!(esc("NUL.txt"), "_4EUL.txt");
+    assert_eq!(unescape_str(&esc("~$a"), '_'), "~$a");
+}
+
+#[test]
+fn posix() {
+    let esc = |s| escape_str(s, '_', Mode::POSIX);
+    assert_eq!(esc("hello-world_1.txt"), "hello-world_5F1.txt");
+    assert_eq!(esc("a b:c/d"), "a_20b_3Ac_2Fd");
+    assert_eq!(esc("-rf"), "_2Drf");
+    assert_eq!(esc("a-"), "a-");
+    assert_eq!(esc("."), "_2E");
+    assert_eq!(esc(".."), "_2E.");
+    assert_eq!(esc("..."), "...");
+    assert_eq!(esc("a~+"), "a_7E_2B");
+    assert_eq!(esc("ž"), "_u017E");
+    assert_eq!(esc(""), "");
+    assert_eq!(
+        filesan::escape_bytes("-ž".as_bytes(), b'_', Mode::POSIX),
+        b"_2D_C5_BE"
+    );
+    assert!(filesan::allowed_str("a-b.c_D", Mode::POSIX));
+    assert!(!filesan::allowed_str("a-ž", Mode::POSIX));
+    assert!(is_reserved("-a", Mode::POSIX));
+    assert!(!is_reserved("-a", Mode::UNIX));
+
+    for c in (0..0x200).filter_map(char::from_u32) {
+        assert_eq!(
+            filesan::allowed(c, Mode::POSIX),
+            c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')
+        );
+    }
+
+    for name in NAMES.iter().chain(&["-", "-a-", "--", " -"]) {
+        let res = escape_str(name, '_', Mode::POSIX | Mode::WINDOWS);
+        assert!(!res.starts_with('-'));
+        assert!(res.chars().all(
+            |c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')
+        ));
+        assert_eq!(
+            escape_chars(name.chars(), '_', Mode::POSIX),
+            escape_str(name, '_', Mode::POSIX)
+        );
+        if name.is_ascii() {
+            assert_eq!(
+                filesan::escape_bytes(name.as_bytes(), b'_', Mode::POSIX),
+                escape_str(name, '_', Mode::POSIX).as_bytes()
+            );
+        }
+    }
+}
+
+#[test]
+fn ascii_only() {
+    let esc = |s| escape_str(s, '_', Mode::ASCII_ONLY);
+    assert_eq!(esc("hello.txt"), "hello.txt");
+    assert_eq!(esc("\x7F"), "\x7F");
+    assert_eq!(esc("\u{80}"), "_80");
+    assert_eq!(esc("é"), "_E9");
+    assert_eq!(esc("žluť"), "_u017Elu_u0165");
+    assert_eq!(esc("€"), "_u20AC");
+    assert_eq!(esc("\u{FFFF}"), "_uFFFF");
+    assert_eq!(esc("😀.txt"), "_U01F600.txt");
+    assert_eq!(esc("\u{10FFFF}"), "_U10FFFF");
+    assert_eq!(
+        escape_str("a:ž ", '_', Mode::ASCII_ONLY | Mode::WINDOWS),
+        "a_3A_u017E_20"
+    );
+    assert_eq!(
+        filesan::escape_bytes("ž".as_bytes(), b'_', Mode::ASCII_ONLY),
+        b"_C5_BE"
+    );
+    assert!(filesan::allowed_str("a b", Mode::ASCII_ONLY));
+    assert!(!filesan::allowed_str("ž", Mode::ASCII_ONLY));
+
+    for c in (0..0x300).filter_map(char::from_u32) {
+        assert_eq!(filesan::allowed(c, Mode::ASCII_ONLY), c.is_ascii());
+    }
+
+    for name in NAMES.iter().chain(&["😀€ž\u{80}", "_u017E", "_U01F600"])
+    {
+        let mode = Mode::ASCII_ONLY | Mode::ALL;
+        let res = escape_str(name, '_', mode);
+        assert!(res.is_ascii());
+        assert_eq!(unescape_str(&res, '_'), *name);
+        assert_eq!(escape_chars(name.chars(), '_', mode), res);
+    }
+}
+
+#[test]
+fn raw() {
+    let name = "__my_file__name_5F_";
+    assert_eq!(
+        escape_str(name, '_', Mode::NONE),
+        "_5F_5Fmy_5Ffile_5F_5Fname_5F5F_5F"
+    );
+    assert_eq!(escape_str_raw(name, '_', Mode::NONE), name);
+    assert_eq!(escape_str_raw("a_b/c:", '_', Mode::WINDOWS), "a_b_2Fc_3A");
+    assert_eq!(escape_str_raw("_.", '_', Mode::WINDOWS), "__2E");
+    assert_eq!(
+        escape_str_raw("a_2F", '_', Mode::UNIX),
+        escape_str_raw("a/", '_', Mode::UNIX)
+    );
+
+    for name in NAMES {
+        for mode in modes() {
+            if !name.contains('_') {
+                assert_eq!(
+                    escape_str_raw(name, '_', mode),
+                    escape_str(name, '_', mode)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn lowercase() {
+    let lower = |s| escape_str_cased(s, '_', Mode::ALL, HexCase::Lower);
+    assert_eq!(lower("a/b:C"), "a_2fb_3aC");
+    assert_eq!(lower("NUL."), "_4eUL_2e");
+    assert_eq!(
+        escape_str_cased("žA😀", '_', Mode::ASCII_ONLY, HexCase::Lower),
+        "_u017eA_U01f600"
+    );
+    assert_eq!(HexCase::default(), HexCase::Upper);
+
+    let mut seen = HashSet::new();
+    for name in NAMES
+        .iter()
+        .chain(&["a_2F", "a_2f", "A/", "a/", "_3A", ":"])
+    {
+        for mode in modes() {
+            for case in [HexCase::Upper, HexCase::Lower] {
+                let res = escape_str_cased(name, '_', mode, case);
+                assert_eq!(unescape_str(&res, '_'), *name);
+                assert_eq!(
+                    res.to_ascii_uppercase(),
+                    escape_str(name, '_', mode).to_ascii_uppercase()
+                );
+            }
+        }
+        assert!(seen.insert(escape_str_cased(
+            name,
+            '_',
+            Mode::ALL,
+            HexCase::Lower
+        )));
+    }
+}
+
+#[test]
+fn idempotent() {
+    let idem = |s| escape_str_idempotent(s, '_', Mode::WINDOWS);
+    assert_eq!(idem("a_2Fb"), "a_2Fb");
+    assert_eq!(idem("a/b"), "a_2Fb");
+    assert_eq!(idem("a_b"), "a_5Fb");
+    assert_eq!(idem("a_2fb"), "a_2fb");
+    assert_eq!(idem("a_2fb_3A"), "a_2fb_3A");
+    assert_eq!(idem("a_2gb"), "a_5F2gb");
+    assert_eq!(idem("a_2"), "a_5F2");
+    assert_eq!(idem("_"), "_5F");
+    assert_eq!(idem("__41"), "_5F_41");
+    assert_eq!(idem("NUL."), "_4EUL_2E");
+    assert_eq!(idem("a_u017E_uFFFF"), "a_u017E_uFFFF");
+    assert_eq!(idem("a_U01F600"), "a_U01F600");
+    assert_eq!(idem("a_u017e_uffff"), "a_u017e_uffff");
+    assert_eq!(idem("a_U01f600"), "a_U01f600");
+    assert_eq!(idem("a_u00E9"), "a_5Fu00E9");
+    assert_eq!(idem("a_u00e9"), "a_5Fu00e9");
+    assert_eq!(idem("a_uD800"), "a_5FuD800");
+
+    // Pseudo random names.
+    const ALPHABET: &[char] = &[
+        '_', '2', 'F', 'f', 'a', '.', ' ', '/', 'N', 'U', 'L', 'ž', '5', ':',
+    ];
+    let mut x = 1u32;
+    for _ in 0..20000 {
+        let mut name = String::new();
+        x = x.wrapping_mul(1103515245).wrapping_add(12345);
+        for _ in 0..(x >> 16) % 8 {
+            x = x.wrapping_mul(1103515245).wrapping_add(12345);
+            name.push(ALPHABET[(x >> 16) as usize % ALPHABET.len()]);
+        }
+        for mode in MODES {
+            let res = escape_str_idempotent(&name, '_', *mode);
+            assert_eq!(escape_str_idempotent(&res, '_', *mode), res);
+            assert!(!needs_escape(&res, '~', *mode));
+            let once = escape_str(&name, '_', *mode);
+            assert_eq!(escape_str_idempotent(&once, '_', *mode), once);
+            let once = escape_str_cased(&name, '_', *mode, HexCase::Lower);
+            assert_eq!(escape_str_idempotent(&once, '_', *mode), once);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use filesan::{
+    Escaped, Mode, escape_char, escape_iter, escape_str, is_reserved,
+};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn display() {
+    for mode in modes() {
+        for name in NAMES {
+            assert_eq!(
+                Escaped::new(name, '_', mode).to_string(),
+                escape_str(name, '_', mode)
+            );
+        }
+    }
+
+    assert_eq!(
+        format!("{}/{}", "dir", Escaped::new("a:b.", '_', Mode::WINDOWS)),
+        "dir/a_3Ab_2E"
+    );
+}
+
+#[test]
+fn debug() {
+    for mode in modes() {
+        for name in NAMES {
+            assert_eq!(
+                format!("{:?}", Escaped::new(name, '_', mode)),
+                format!("{:?}", escape_str(name, '_', mode))
+            );
+        }
+    }
+}
+
+#[test]
+fn iter() {
+    for mode in modes() {
+        for name in NAMES.iter().chain(&["€", "a€b.", "\u{10FFFF}:"]) {
+            for esc in ['_', 'é', '€', '\u{10FFFF}'] {
+                let res = escape_str(name, esc, mode);
+                let mut it = escape_iter(name, esc, mode);
+                let mut cnt = res.chars().count();
+                loop {
+                    let (lo, hi) = it.size_hint();
+                    assert!(lo <= cnt && cnt <= hi.unwrap());
+                    if it.next().is_none() {
+                        break;
+                    }
+                    cnt -= 1;
+                }
+                assert_eq!(cnt, 0);
+                assert_eq!(it.next(), None);
+                assert_eq!(
+                    escape_iter(name, esc, mode).collect::<String>(),
+                    res
+                );
+            }
+        }
+    }
+
+    let it = escape_iter("a:", '_', Mode::WINDOWS);
+    assert_eq!(it.clone().collect::<String>(), it.collect::<String>());
+}
+
+#[test]
+fn char() {
+    assert_eq!(&*escape_char('a', '_', Mode::ALL), "a");
+    assert_eq!(&*escape_char('_', '_', Mode::NONE), "_5F");
+    assert_eq!(&*escape_char('\0', '_', Mode::UNIX), "_00");
+    assert_eq!(&*escape_char('.', '_', Mode::ALL), ".");
+    assert_eq!(&*escape_char('€', '€', Mode::ALL), "€u20AC");
+    assert_eq!(escape_char('ž', '_', Mode::ALL).to_string(), "ž");
+    assert_eq!(format!("{:?}", escape_char('/', '_', Mode::ALL)), "\"_2F\"");
+
+    // Without reserved names and trailing characters, escaping characters
+    // one by one is the same as escaping the whole string.
+    const ALPHABET: &[char] = &[
+        'a',
+        '.',
+        ' ',
+        '_',
+        '/',
+        ':',
+        '\0',
+        '*',
+        'ž',
+        '€',
+        '\u{10FFFF}',
+    ];
+    let mut names = vec![String::new()];
+    for _ in 0..4 {
+        names = names
+            .iter()
+            .flat_map(|n| ALPHABET.iter().map(move |c| format!("{n}{c}")))
+            .collect();
+        for mode in [Mode::NONE, Mode::UNIX, Mode::MAC, Mode::WINDOWS] {
+            for name in &names {
+                if is_reserved(name, mode)
+                    || (mode == Mode::WINDOWS
+                        && (name.ends_with('.') || name.ends_with(' ')))
+                {
+                    continue;
+                }
+                for esc in ['_', '€'] {
+                    let res: String = name
+                        .chars()
+                        .map(|c| escape_char(c, esc, mode).to_string())
+                        .collect();
+                    assert_eq!(res, escape_str(name, esc, mode));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use filesan::{Mode, SanitizeExt, escape_str};
+
+#[test]
+fn sanitize() {
+    assert_eq!("a/b_c".sanitize(Mode::UNIX), "a_2Fb_5Fc");
+    assert_eq!("NUL.txt".sanitize(Mode::WINDOWS), "_4EUL.txt");
+    assert_eq!("a:b".sanitize(Mode::UNIX), "a:b");
+    assert_eq!("a/b_c".sanitize_with('~', Mode::UNIX), "a~2Fb_c");
+
+    let name = String::from("file. ");
+    assert_eq!(name.sanitize(Mode::WINDOWS), "file_2E_20");
+    assert_eq!(name.sanitize_with('~', Mode::ALL), "file~2E~20");
+    assert_eq!(
+        name.as_str().sanitize(Mode::ALL),
+        escape_str(&name, '_', Mode::ALL)
+    );
+}
@@ -0,0 +1,52 @@
+use filesan::{
+    LOOKALIKES, Mode, allowed, escape_str, escape_str_lookalike, needs_escape,
+};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn table() {
+    for (c, l) in LOOKALIKES {
+        assert!(!allowed(*c, Mode::ALL));
+        for mode in
+            modes().filter(|m| !m.intersects(Mode::POSIX | Mode::ASCII_ONLY))
+        {
+            assert!(allowed(*l, mode), "{l:?} {mode}");
+        }
+    }
+
+    // Every printable character disallowed on the supported systems has
+    // lookalike.
+    for c in (' '..'\x7f').filter(|c| !allowed(*c, Mode::ALL)) {
+        assert!(LOOKALIKES.iter().any(|(d, _)| *d == c), "{c:?}");
+    }
+}
+
+#[test]
+fn lookalike() {
+    let esc = |s, mode| escape_str_lookalike(s, '_', mode, LOOKALIKES);
+    assert_eq!(esc("a/b:c?\x01", Mode::ALL), "a∕b꞉c？_01");
+    assert_eq!(esc("a<b>c\"d|e*f\\g", Mode::WINDOWS), "a＜b＞c＂d｜e＊f⧵g");
+    assert_eq!(esc("a:b", Mode::UNIX), "a:b");
+    assert_eq!(esc("a/b", Mode::POSIX), "a_2Fb");
+    assert_eq!(esc("a/b", Mode::UNIX | Mode::ASCII_ONLY), "a_2Fb");
+    assert_eq!(esc("NUL.a:.", Mode::WINDOWS), "_4EUL.a꞉_2E");
+    assert_eq!(esc("a_/", Mode::UNIX), "a_5F∕");
+
+    // Custom table
+    let res = escape_str_lookalike("a/b:c", '_', Mode::ALL, &[('/', '-')]);
+    assert_eq!(res, "a-b_3Ac");
+
+    for name in NAMES {
+        for mode in modes() {
+            let res = esc(name, mode);
+            assert!(!needs_escape(&res, '\u{10FFFF}', mode), "{res:?}");
+            assert_eq!(
+                escape_str_lookalike(name, '_', mode, &[]),
+                escape_str(name, '_', mode)
+            );
+        }
+    }
+}
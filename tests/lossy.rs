@@ -0,0 +1,98 @@
+use filesan::{
+    Mode, needs_escape, replace_lossy, sanitize_replace,
+    sanitize_replace_collapsed,
+};
+
+mod common;
+
+use common::{NAMES, UNUSED_ESC, modes};
+
+#[test]
+fn lossy() {
+    let rep = |s, collapse| replace_lossy(s, '_', Mode::WINDOWS, collapse);
+    assert_eq!(rep("foo:bar?.txt", false), "foo_bar_.txt");
+    assert_eq!(rep("foo:bar?.txt", true), "foo_bar_.txt");
+    assert_eq!(rep("a::b", false), "a__b");
+    assert_eq!(rep("a::b", true), "a_b");
+    assert_eq!(rep("a_:b", true), "a__b");
+    assert_eq!(rep("a. .", false), "a___");
+    assert_eq!(rep("a. .", true), "a_");
+
+    // All invalid
+    assert_eq!(rep("<>:\"|?*", false), "_______");
+    assert_eq!(rep("<>:\"|?*", true), "_");
+    assert_eq!(replace_lossy("//", '_', Mode::UNIX, true), "_");
+
+    // Reserved names
+    assert_eq!(rep("NUL.txt", true), "_UL.txt");
+    assert_eq!(rep("com1.tar.gz", true), "_om1.tar.gz");
+    assert_eq!(rep("CON.", true), "_ON_");
+    assert_eq!(replace_lossy("..", '_', Mode::UNIX, false), "_.");
+    assert_eq!(replace_lossy("-a", '_', Mode::POSIX, false), "_a");
+
+    for name in NAMES {
+        for mode in modes() {
+            for collapse in [false, true] {
+                let res = replace_lossy(name, '_', mode, collapse);
+                assert!(
+                    !needs_escape(&res, UNUSED_ESC, mode),
+                    "{res:?} {mode}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn sanitize() {
+    assert_eq!(
+        sanitize_replace("my/file.txt", '_', Mode::UNIX),
+        "my_file.txt"
+    );
+    assert_eq!(sanitize_replace("a//b", '_', Mode::UNIX), "a_b");
+    assert_eq!(sanitize_replace("a: b?. ", '-', Mode::WINDOWS), "a- b-");
+    assert_eq!(sanitize_replace("con.txt", '_', Mode::WINDOWS), "_on.txt");
+
+    // Empty names
+    assert_eq!(sanitize_replace("", '_', Mode::ALL), "_");
+    assert_eq!(sanitize_replace("", '-', Mode::NONE), "-");
+
+    for name in NAMES.iter().filter(|n| !n.is_empty()) {
+        for mode in modes() {
+            assert_eq!(
+                sanitize_replace(name, '_', mode),
+                replace_lossy(name, '_', mode, true)
+            );
+        }
+    }
+}
+
+#[test]
+fn collapsed() {
+    let san = |s| sanitize_replace_collapsed(s, '_', Mode::WINDOWS);
+    assert_eq!(san("a///b"), "a_b");
+    assert_eq!(san("//a//b//"), "a_b");
+    assert_eq!(san("a:b. ."), "a_b");
+    assert_eq!(san("a.:"), "a");
+    assert_eq!(san("_a_"), "_a_");
+    assert_eq!(san("a__b"), "a__b");
+
+    // All invalid
+    assert_eq!(san("<>:\"|?*"), "_");
+    assert_eq!(san(". ."), "_");
+    assert_eq!(san(""), "_");
+    assert_eq!(sanitize_replace_collapsed("/", '-', Mode::UNIX), "-");
+
+    // Reserved names
+    assert_eq!(san("NUL.txt"), "_UL.txt");
+    assert_eq!(san("?con"), "_on");
+    assert_eq!(sanitize_replace_collapsed("/..", '_', Mode::UNIX), "_.");
+
+    for name in NAMES {
+        for mode in modes() {
+            let res = sanitize_replace_collapsed(name, '_', mode);
+            assert!(!res.is_empty());
+            assert!(!needs_escape(&res, UNUSED_ESC, mode), "{res:?} {mode}");
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use filesan::Mode;
+
+#[test]
+fn parse() {
+    assert_eq!("none".parse(), Ok(Mode::NONE));
+    assert_eq!("unix".parse(), Ok(Mode::UNIX));
+    assert_eq!("WINDOWS".parse(), Ok(Mode::WINDOWS));
+    assert_eq!("Mac".parse(), Ok(Mode::MAC));
+    assert_eq!("all".parse(), Ok(Mode::ALL));
+    assert_eq!("system".parse(), Ok(Mode::SYSTEM));
+    assert_eq!("windows_end".parse(), Ok(Mode::WINDOWS_END));
+    assert_eq!("ascii_only".parse(), Ok(Mode::ASCII_ONLY));
+    assert_eq!("fat".parse(), Ok(Mode::FAT));
+    assert_eq!("hfs".parse(), Ok(Mode::HFS));
+    assert_eq!("cloud".parse(), Ok(Mode::CLOUD));
+    assert_eq!("fat+unix".parse(), Ok(Mode::FAT | Mode::UNIX));
+    assert_eq!("unix,mac".parse(), Ok(Mode::UNIX | Mode::MAC));
+    assert_eq!(" unix | windows ".parse(), Ok(Mode::UNIX_WINDOWS));
+    assert_eq!("unix,windows|mac".parse(), Ok(Mode::ALL));
+    assert_eq!("none,unix".parse(), Ok(Mode::UNIX));
+    assert_eq!("unix,unix".parse(), Ok(Mode::UNIX));
+
+    let err = "unix,linux".parse::<Mode>().unwrap_err();
+    assert_eq!(err.token(), "linux");
+    assert_eq!(err.to_string(), "Unknown mode `linux`.");
+    assert_eq!("".parse::<Mode>().unwrap_err().token(), "");
+    assert_eq!(" , unix".parse::<Mode>().unwrap_err().token(), "");
+    assert_eq!("unix,".parse::<Mode>().unwrap_err().token(), "");
+    assert_eq!("unix-mac".parse::<Mode>().unwrap_err().token(), "unix-mac");
+    assert_eq!("0x".parse::<Mode>().unwrap_err().token(), "0x");
+    assert_eq!("0x30".parse(), Ok(Mode::from_bits_retain(0x30)));
+}
+
+#[test]
+fn display() {
+    assert_eq!(Mode::NONE.to_string(), "none");
+    assert_eq!(Mode::UNIX.to_string(), "unix");
+    assert_eq!(Mode::MAC.to_string(), "mac");
+    assert_eq!(Mode::UNIX_WINDOWS.to_string(), "unix+windows");
+    assert_eq!(Mode::ALL.to_string(), "unix+windows+mac");
+    assert_eq!(
+        (Mode::WINDOWS | Mode::WINDOWS_END).to_string(),
+        "windows+windows_end"
+    );
+    assert_eq!(Mode::from_bits_retain(0x1001).to_string(), "unix+0x1000");
+    assert_eq!(
+        (Mode::POSIX | Mode::ASCII_ONLY).to_string(),
+        "posix+ascii_only"
+    );
+
+    assert_eq!(Mode::FAT.to_string(), "fat");
+    assert_eq!(Mode::HFS.to_string(), "hfs");
+    assert_eq!(Mode::CLOUD.to_string(), "cloud");
+    assert_eq!((Mode::CLOUD | Mode::FAT).to_string(), "fat+cloud");
+    assert_eq!((Mode::FAT | Mode::ALL).to_string(), "fat+unix+mac");
+    assert_eq!(Mode::from_bits_retain(0x40).to_string(), "0x40");
+
+    for mode in (0..0x200).map(Mode::from_bits_retain) {
+        assert_eq!(mode.to_string().parse(), Ok(mode));
+    }
+}
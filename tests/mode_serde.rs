@@ -0,0 +1,55 @@
+#![cfg(feature = "serde")]
+
+use filesan::Mode;
+
+mod common;
+
+use common::modes;
+
+#[test]
+fn serialize() {
+    let ser = |m: Mode| serde_json::to_string(&m).unwrap();
+    assert_eq!(ser(Mode::NONE), "\"none\"");
+    assert_eq!(ser(Mode::UNIX | Mode::WINDOWS), "\"unix+windows\"");
+    assert_eq!(ser(Mode::FAT | Mode::POSIX), "\"fat+posix\"");
+}
+
+#[test]
+fn deserialize() {
+    let de = |s: &str| serde_json::from_str::<Mode>(s);
+    assert_eq!(de("\"unix+windows\"").unwrap(), Mode::UNIX | Mode::WINDOWS);
+    assert_eq!(de("\"ALL\"").unwrap(), Mode::ALL);
+    assert_eq!(de("[\"windows\", \"Unix\"]").unwrap(), Mode::UNIX_WINDOWS);
+    assert_eq!(de("[]").unwrap(), Mode::NONE);
+    assert_eq!(de("3").unwrap(), Mode::UNIX_WINDOWS);
+    assert_eq!(de("4096").unwrap(), Mode::from_bits_retain(0x1000));
+
+    let err = de("\"unix+linux\"").unwrap_err().to_string();
+    assert!(err.contains("Unknown mode `linux`."), "{err}");
+    let err = de("[\"unix\", \"linux\"]").unwrap_err().to_string();
+    assert!(err.contains("Unknown mode `linux`."), "{err}");
+    assert!(de("-1").is_err());
+    assert!(de("4294967296").is_err());
+    assert!(de("true").is_err());
+}
+
+#[test]
+fn binary() {
+    let ser = |m: Mode| bincode::serialize(&m).unwrap();
+    assert_eq!(ser(Mode::WINDOWS), 2u32.to_le_bytes());
+    assert_eq!(ser(Mode::FAT), 0x42u32.to_le_bytes());
+
+    let de = |b: &[u8]| bincode::deserialize::<Mode>(b);
+    assert_eq!(de(&3u32.to_le_bytes()).unwrap(), Mode::UNIX_WINDOWS);
+    assert!(de(&[1]).is_err());
+}
+
+#[test]
+fn round_trip() {
+    for mode in modes().chain([Mode::from_bits_retain(0x1000)]) {
+        let s = serde_json::to_string(&mode).unwrap();
+        assert_eq!(serde_json::from_str::<Mode>(&s).unwrap(), mode, "{s}");
+        let b = bincode::serialize(&mode).unwrap();
+        assert_eq!(bincode::deserialize::<Mode>(&b).unwrap(), mode);
+    }
+}
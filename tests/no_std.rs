@@ -0,0 +1,22 @@
+//! Check that the crate can be used from `no_std` crate. Run with
+//! `cargo test --no-default-features` to check that the crate itself doesn't
+//! depend on `std`. This runs on the host, so also check that the crate
+//! builds for target without operating system with
+//! `cargo build --no-default-features --target thumbv7em-none-eabihf`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+use filesan::{Mode, allowed, escape_str, unescape_str};
+
+#[test]
+fn no_std() {
+    const { assert!(allowed(':', Mode::UNIX)) };
+
+    let res: String = escape_str("NUL.a:b.", '_', Mode::WINDOWS);
+    assert_eq!(res, "_4EUL.a_3Ab_2E");
+    assert_eq!(unescape_str(&res, '_'), "NUL.a:b.");
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "std")]
+
+use std::ffi::OsStr;
+
+use filesan::{Mode, escape_os_str, escape_str};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn os_str() {
+    for name in NAMES.iter().chain(&["žluťoučký kůň. ", "\u{FFFD}."]) {
+        for mode in modes() {
+            for esc in ['_', '€'] {
+                assert_eq!(
+                    escape_os_str(OsStr::new(name), esc, mode),
+                    OsStr::new(&escape_str(name, esc, mode))
+                );
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn os_str_invalid() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let esc = |b: &[u8], mode| escape_os_str(OsStr::from_bytes(b), '_', mode);
+
+    assert_eq!(esc(b"a\xFFb", Mode::UNIX), "a_u00FFb");
+    assert_eq!(esc(b"\xC5\xBE\xC5", Mode::UNIX), "ž_u00C5");
+    assert_eq!(esc(b"nul\xFF.txt", Mode::WINDOWS), "nul_u00FF.txt");
+    assert_eq!(esc(b"nul.\xFF", Mode::WINDOWS), "_6Eul._u00FF");
+    assert_eq!(esc(b"a/\x80.", Mode::WINDOWS), "a_2F_u0080_2E");
+    assert_eq!(esc(b"\xFF\xFF", Mode::NONE), "_u00FF_u00FF");
+}
+
+#[cfg(unix)]
+#[test]
+fn os_str_invalid_unique() {
+    use std::os::unix::ffi::OsStrExt;
+
+    for mode in (0..0x200).map(Mode::from_bits_retain) {
+        for esc in ['_', 'é', 'ÿ', '€'] {
+            let esc = |s: &OsStr| escape_os_str(s, esc, mode);
+            for b in 0x80..=0xFFu8 {
+                let c = char::from(b).to_string();
+                assert_ne!(
+                    esc(OsStr::from_bytes(&[b])),
+                    esc(OsStr::new(&c)),
+                    "{b:#x} {mode}"
+                );
+            }
+            assert_ne!(esc(OsStr::from_bytes(b"\xFF")), esc(OsStr::new("ÿ")));
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use filesan::{
+    Mode, escape_path, escape_str, sanitize_component, unescape_str,
+};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn path() {
+    assert_eq!(escape_path("a/b:c/d", '_', Mode::WINDOWS), "a/b_3Ac/d");
+    assert_eq!(escape_path("a/b:c/d", '_', Mode::UNIX), "a/b:c/d");
+    assert_eq!(escape_path("/a//b/", '_', Mode::ALL), "/a//b/");
+    assert_eq!(escape_path("", '_', Mode::ALL), "");
+    assert_eq!(escape_path("/", '_', Mode::ALL), "/");
+    assert_eq!(escape_path("a\\b/c", '_', Mode::UNIX), "a\\b/c");
+    assert_eq!(escape_path("a\\b/c", '_', Mode::WINDOWS), "a\\b/c");
+    assert_eq!(
+        escape_path("a.\\nul /c", '_', Mode::WINDOWS),
+        "a_2E\\nul_20/c"
+    );
+    assert_eq!(escape_path("../a_b", '_', Mode::UNIX), "_2E./a_5Fb");
+    assert_eq!(escape_path("ž/€", '€', Mode::ALL), "ž/€u20AC");
+
+    for name in NAMES.iter().filter(|n| !n.contains(['/', '\\'])) {
+        for mode in modes() {
+            assert_eq!(
+                escape_path(name, '_', mode),
+                escape_str(name, '_', mode)
+            );
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "std"))]
+#[test]
+fn sanitize() {
+    use std::path::Path;
+
+    use filesan::sanitize_path;
+
+    let san = |p: &str, mode| sanitize_path(Path::new(p), '_', mode);
+
+    assert_eq!(san("a/b:c/d", Mode::WINDOWS), Path::new("a/b_3Ac/d"));
+    assert_eq!(san("/a//./b/", Mode::ALL), Path::new("/a/b"));
+    assert_eq!(san("./../a/..", Mode::ALL), Path::new("./../a/.."));
+    assert_eq!(san("", Mode::ALL), Path::new(""));
+    assert_eq!(san("/", Mode::ALL), Path::new("/"));
+    assert_eq!(san("a\\b/c d.", Mode::UNIX), Path::new("a\\b/c d."));
+    assert_eq!(san("a\\b/c d.", Mode::WINDOWS), Path::new("a_5Cb/c d_2E"));
+    assert_eq!(
+        san("/dir/aux.txt", Mode::WINDOWS),
+        Path::new("/dir/_61ux.txt")
+    );
+}
+
+#[test]
+fn component() {
+    for mode in modes() {
+        let san = |n| sanitize_component(n, '_', mode);
+        for name in NAMES.iter().chain(&[
+            "/", "\\", "a/b", "../..", "..\\a", "/..", ". .", "a_b", "ž/€",
+        ]) {
+            let res = san(name);
+            assert!(!res.contains(['/', '\\']), "{name:?} {mode:?}");
+            assert!(res != "." && res != "..", "{name:?} {mode:?}");
+            assert_eq!(unescape_str(&res, '_'), *name);
+        }
+        assert_eq!(san(""), "");
+        assert_eq!(san("."), "_2E");
+        assert_eq!(san("a\\b/c"), "a_5Cb_2Fc");
+    }
+
+    assert_eq!(sanitize_component("..", '_', Mode::NONE), "_2E.");
+    assert_eq!(sanitize_component("..", '_', Mode::WINDOWS), "_2E_2E");
+    assert_eq!(sanitize_component("../x", '_', Mode::NONE), ".._2Fx");
+    assert_eq!(sanitize_component("aux", '_', Mode::WINDOWS), "_61ux");
+    assert_eq!(sanitize_component("a:b", '_', Mode::MAC), "a_3Ab");
+    assert_eq!(sanitize_component("a:b", '_', Mode::UNIX), "a:b");
+}
@@ -0,0 +1,60 @@
+use filesan::{Edit, Mode, escape_str, plan_escape};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn plan() {
+    let plan = plan_escape("hello.txt", '_', Mode::ALL);
+    assert!(plan.is_empty());
+    assert_eq!(plan.apply("hello.txt"), "hello.txt");
+
+    let plan = plan_escape("NUL.ž:", '_', Mode::WINDOWS);
+    assert_eq!(
+        plan.edits,
+        [
+            Edit {
+                start: 0,
+                end: 1,
+                replacement: "_4E".into(),
+            },
+            Edit {
+                start: 6,
+                end: 7,
+                replacement: "_3A".into(),
+            },
+        ]
+    );
+
+    for name in NAMES {
+        for mode in modes() {
+            for esc in ['_', '€'] {
+                let plan = plan_escape(name, esc, mode);
+                let res = escape_str(name, esc, mode);
+                assert_eq!(plan.apply(name), res);
+                assert_eq!(plan.is_empty(), &res == name);
+            }
+        }
+    }
+
+    const ALPHABET: &[char] =
+        &['a', 'n', 'u', 'l', 'N', '.', ' ', '_', '/', ':', 'ž', '€'];
+    let mut names = vec![String::new()];
+    for _ in 0..4 {
+        names = names
+            .iter()
+            .flat_map(|n| ALPHABET.iter().map(move |c| format!("{n}{c}")))
+            .collect();
+        for mode in modes() {
+            for name in &names {
+                for esc in ['_', '€'] {
+                    let plan = plan_escape(name, esc, mode);
+                    let res = escape_str(name, esc, mode);
+                    assert_eq!(plan.apply(name), res);
+                    assert_eq!(plan.is_empty(), &res == name);
+                }
+            }
+        }
+    }
+}
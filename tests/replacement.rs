@@ -0,0 +1,50 @@
+use filesan::{Mode, Reason, Replacement, escape_str, escape_str_with};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn with() {
+    let res = escape_str_with("a:b/c\x01", '_', Mode::ALL, |c, _| match c {
+        ':' => Replacement::Str("∶".into()),
+        '/' => Replacement::Str("⁄".into()),
+        _ => Replacement::Hex,
+    });
+    assert_eq!(res, "a∶b⁄c_01");
+
+    let mut reasons = vec![];
+    let res = escape_str_with("NUL.a_:. ", '_', Mode::WINDOWS, |c, r| {
+        reasons.push((c, r));
+        match r {
+            Reason::Reserved => Replacement::Str(String::from("_N").into()),
+            Reason::Escape => Replacement::Keep,
+            Reason::Disallowed => Replacement::Remove,
+            Reason::Trailing => Replacement::Hex,
+        }
+    });
+    assert_eq!(res, "_NUL.a__2E_20");
+    assert_eq!(
+        reasons,
+        [
+            ('N', Reason::Reserved),
+            ('_', Reason::Escape),
+            (':', Reason::Disallowed),
+            ('.', Reason::Trailing),
+            (' ', Reason::Trailing),
+        ]
+    );
+
+    for name in NAMES {
+        for mode in modes() {
+            assert_eq!(
+                escape_str_with(name, '_', mode, |_, _| Replacement::Hex),
+                escape_str(name, '_', mode)
+            );
+            assert_eq!(
+                escape_str_with(name, '_', mode, |_, _| Replacement::Keep),
+                *name
+            );
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use filesan::{EscapeOutcome, Mode, escape_str, escape_str_report};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn report() {
+    assert_eq!(
+        escape_str_report("hello.txt", '_', Mode::ALL),
+        EscapeOutcome {
+            name: "hello.txt".into(),
+            changed: false,
+            replaced: 0,
+            reserved_prefixed: false,
+            trailing_fixed: false,
+        }
+    );
+
+    assert_eq!(
+        escape_str_report("con_a:b. .", '_', Mode::ALL),
+        EscapeOutcome {
+            name: "con_5Fa_3Ab_2E_20_2E".into(),
+            changed: true,
+            replaced: 5,
+            reserved_prefixed: false,
+            trailing_fixed: true,
+        }
+    );
+
+    // Triggers all the modifications at once.
+    assert_eq!(
+        escape_str_report("con.a_b:c..", '_', Mode::WINDOWS),
+        EscapeOutcome {
+            name: "_63on.a_5Fb_3Ac_2E_2E".into(),
+            changed: true,
+            replaced: 5,
+            reserved_prefixed: true,
+            trailing_fixed: true,
+        }
+    );
+
+    for mode in [Mode::WINDOWS, Mode::ALL] {
+        let res = escape_str_report("a:.", '_', mode);
+        assert_eq!(res.name, "a_3A_2E");
+        assert_eq!(res.replaced, 2);
+        assert!(res.trailing_fixed);
+    }
+    assert!(escape_str_report("..", '_', Mode::ALL).trailing_fixed);
+    assert!(!escape_str_report("a.", '_', Mode::UNIX).trailing_fixed);
+
+    for name in NAMES.iter().chain(&["a.", "a_", "žluť. ", "a:.", "a_ "]) {
+        for mode in modes() {
+            let res = escape_str_report(name, '_', mode);
+            let esc = escape_str(name, '_', mode);
+            assert_eq!(res.changed, esc != *name);
+            assert_eq!(res.name, esc);
+            assert_eq!(
+                res.trailing_fixed,
+                mode.intersects(Mode::WINDOWS) && name.ends_with([' ', '.']),
+                "{name:?} {mode}"
+            );
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use filesan::{
+    CLOUD_RESERVED, Mode, UNIX_RESERVED, WINDOWS_RESERVED, escape_str,
+    is_reserved, reserved_names,
+};
+
+#[test]
+fn reserved() {
+    for name in WINDOWS_RESERVED {
+        assert!(is_reserved(name, Mode::WINDOWS));
+        assert!(is_reserved(&format!("{name}.txt"), Mode::WINDOWS));
+        assert!(!is_reserved(name, Mode::UNIX | Mode::MAC));
+    }
+    for name in UNIX_RESERVED {
+        assert!(is_reserved(name, Mode::UNIX));
+        assert!(is_reserved(name, Mode::MAC));
+        assert!(!is_reserved(name, Mode::WINDOWS));
+    }
+
+    for name in ["nul", "Nul", "nUL.txt", "CoM1", "lpt9.TXT", "aux", "Prn"] {
+        assert!(is_reserved(name, Mode::WINDOWS));
+    }
+
+    assert!(is_reserved("NUL.txt.bak", Mode::WINDOWS));
+    assert!(is_reserved("COM1.a.b.c", Mode::WINDOWS));
+    assert!(is_reserved("con.", Mode::WINDOWS));
+
+    assert!(!is_reserved("", Mode::ALL));
+    assert!(!is_reserved("...", Mode::ALL));
+    assert!(!is_reserved("CONS", Mode::ALL));
+    assert!(!is_reserved("COM", Mode::ALL));
+    assert!(!is_reserved("NUL", Mode::NONE));
+
+    for name in ["NUL", "NUL.txt", "..", "...", "CONS", "hello"] {
+        for mode in [Mode::UNIX, Mode::WINDOWS, Mode::MAC, Mode::ALL] {
+            if is_reserved(name, mode) {
+                assert!(!is_reserved(&escape_str(name, '_', mode), mode));
+            }
+        }
+    }
+}
+
+#[test]
+fn names() {
+    let names = |mode| reserved_names(mode).collect::<Vec<_>>();
+    assert_eq!(names(Mode::NONE), [""; 0]);
+    assert_eq!(names(Mode::UNIX), UNIX_RESERVED);
+    assert_eq!(names(Mode::MAC), UNIX_RESERVED);
+    assert_eq!(names(Mode::POSIX), UNIX_RESERVED);
+    assert_eq!(names(Mode::WINDOWS), WINDOWS_RESERVED);
+    assert_eq!(names(Mode::ALL), [WINDOWS_RESERVED, UNIX_RESERVED].concat());
+    assert_eq!(
+        names(Mode::CLOUD),
+        [WINDOWS_RESERVED, CLOUD_RESERVED].concat()
+    );
+
+    for mode in (0..0x200).map(Mode::from_bits_retain) {
+        let names = names(mode);
+        let all = [WINDOWS_RESERVED, UNIX_RESERVED, CLOUD_RESERVED].concat();
+        for name in all {
+            assert_eq!(is_reserved(name, mode), names.contains(&name));
+        }
+        for name in names {
+            assert_ne!(escape_str(name, '_', mode), name);
+        }
+    }
+}
+
+#[test]
+fn cloud() {
+    for name in [".lock", "desktop.ini", "DESKTOP.ini", " a", "~$a.docx", " "]
+    {
+        assert!(is_reserved(name, Mode::CLOUD), "{name:?}");
+        assert!(!is_reserved(name, Mode::WINDOWS), "{name:?}");
+    }
+    for name in ["CON", "nul.txt"] {
+        assert!(is_reserved(name, Mode::CLOUD));
+    }
+    for name in ["a.lock", ".lock.txt", "desktop", "~a", "a~$", "a "] {
+        assert!(!is_reserved(name, Mode::CLOUD), "{name:?}");
+    }
+}
+
+#[test]
+fn device_names() {
+    for name in [
+        "CONIN$",
+        "conout$",
+        "Clock$.txt",
+        "COM¹",
+        "com².log",
+        "LPT³",
+        "lpt¹",
+    ] {
+        assert!(is_reserved(name, Mode::WINDOWS), "{name:?}");
+        assert!(!is_reserved(name, Mode::UNIX), "{name:?}");
+    }
+    for name in ["CONIN", "CONOUT$$", "CLOCK", "COM⁴", "COM¹²", "LPT¹¹"]
+    {
+        assert!(!is_reserved(name, Mode::WINDOWS), "{name:?}");
+    }
+    assert_eq!(escape_str("COM¹.txt", '_', Mode::WINDOWS), "_43OM¹.txt");
+}
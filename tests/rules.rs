@@ -0,0 +1,173 @@
+use filesan::{
+    LengthUnit, Mode, NameMatch, ReservedName, Rules, UNIX_RESERVED,
+    WINDOWS_RESERVED, allowed, escape_str, is_reserved, rules,
+};
+
+mod common;
+
+use common::{NAMES, UNUSED_ESC, modes};
+
+fn names(names: &[&'static str], matching: NameMatch) -> Vec<ReservedName> {
+    names
+        .iter()
+        .map(|&name| ReservedName { name, matching })
+        .collect()
+}
+
+#[test]
+fn base_modes() {
+    assert_eq!(rules(Mode::NONE), Rules::default());
+
+    assert_eq!(
+        rules(Mode::UNIX),
+        Rules {
+            disallowed: vec!['\0'..='\0', '/'..='/'],
+            reserved: names(UNIX_RESERVED, NameMatch::Exact),
+            trailing: vec![],
+            case_insensitive: false,
+            max_len: None,
+        }
+    );
+
+    assert_eq!(
+        rules(Mode::MAC),
+        Rules {
+            disallowed: vec!['\0'..='\0', '/'..='/', ':'..=':'],
+            reserved: names(UNIX_RESERVED, NameMatch::Exact),
+            trailing: vec![],
+            case_insensitive: true,
+            max_len: None,
+        }
+    );
+
+    assert_eq!(
+        rules(Mode::WINDOWS),
+        Rules {
+            disallowed: vec![
+                '\0'..='\x1F',
+                '"'..='"',
+                '*'..='*',
+                '/'..='/',
+                ':'..=':',
+                '<'..='<',
+                '>'..='?',
+                '\\'..='\\',
+                '|'..='|',
+            ],
+            reserved: names(WINDOWS_RESERVED, NameMatch::Stem),
+            trailing: vec![' ', '.'],
+            case_insensitive: true,
+            max_len: None,
+        }
+    );
+    assert_eq!(
+        rules(Mode::FAT),
+        Rules {
+            max_len: Some((255, LengthUnit::Utf16)),
+            ..rules(Mode::WINDOWS)
+        }
+    );
+    assert_eq!(rules(Mode::CLOUD).max_len, None);
+    assert_eq!(
+        rules(Mode::FAT | Mode::ALL).max_len,
+        rules(Mode::FAT).max_len
+    );
+
+    assert_eq!(rules(Mode::WINDOWS_END).disallowed, [' '..=' ', '.'..='.']);
+
+    assert_eq!(
+        rules(Mode::POSIX),
+        Rules {
+            disallowed: vec![
+                '\0'..=',',
+                '/'..='/',
+                ':'..='@',
+                '['..='^',
+                '`'..='`',
+                '{'..=char::MAX,
+            ],
+            reserved: [
+                names(&["-"], NameMatch::Prefix),
+                names(UNIX_RESERVED, NameMatch::Exact),
+            ]
+            .concat(),
+            trailing: vec![],
+            case_insensitive: false,
+            max_len: None,
+        }
+    );
+
+    assert_eq!(rules(Mode::ASCII_ONLY).disallowed, ['\u{80}'..=char::MAX]);
+
+    assert_eq!(
+        rules(Mode::HFS),
+        Rules {
+            disallowed: vec!['\0'..='\0', ':'..=':'],
+            reserved: vec![],
+            trailing: vec![],
+            case_insensitive: true,
+            max_len: None,
+        }
+    );
+
+    let cloud = rules(Mode::CLOUD);
+    assert_eq!(cloud.disallowed, rules(Mode::WINDOWS).disallowed);
+    assert_eq!(
+        cloud.reserved[WINDOWS_RESERVED.len()..],
+        [
+            names(&[" ", "~$"], NameMatch::Prefix),
+            names(&[".lock", "desktop.ini"], NameMatch::IgnoreCase),
+        ]
+        .concat()
+    );
+}
+
+fn escaped_by_rules(name: &str, rules: &Rules) -> bool {
+    name.chars()
+        .any(|c| rules.disallowed.iter().any(|r| r.contains(&c)))
+        || name.ends_with(rules.trailing.as_slice())
+        || rules.reserved.iter().any(|r| r.matches(name))
+}
+
+#[test]
+fn same_as_escape() {
+    for mode in modes() {
+        let rules = rules(mode);
+        for c in (0..0x100).filter_map(char::from_u32).chain(['ž', '😀']) {
+            let disallowed = rules.disallowed.iter().any(|r| r.contains(&c));
+            assert_eq!(disallowed, !allowed(c, mode), "{c:?} {mode}");
+        }
+        for name in NAMES {
+            assert_eq!(
+                rules.reserved.iter().any(|r| r.matches(name)),
+                is_reserved(name, mode),
+                "{name:?} {mode}"
+            );
+        }
+        for w in rules.disallowed.windows(2) {
+            assert!((*w[0].end() as u32) + 1 < *w[1].start() as u32);
+        }
+    }
+}
+
+#[test]
+fn same_as_classify() {
+    let chars: Vec<_> = (0..0x100)
+        .filter_map(char::from_u32)
+        .chain(['ž', '😀'])
+        .collect();
+    for mode in modes() {
+        let rules = rules(mode);
+        let names = chars
+            .iter()
+            .flat_map(|c| [format!("a{c}a"), format!("{c}a"), format!("a{c}")])
+            .chain(NAMES.iter().map(|n| n.to_string()));
+        for name in names {
+            assert_eq!(
+                escape_str(&name, UNUSED_ESC, mode) != name,
+                escaped_by_rules(&name, &rules),
+                "{name:?} {mode}"
+            );
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use filesan::{
+    DEFAULT_ESC, HexCase, Mode, Sanitizer, escape_str, escape_str_cased,
+    unescape_str,
+};
+
+#[test]
+fn sanitizer() {
+    let san = Sanitizer::new();
+    assert_eq!(san, Sanitizer::default());
+    assert_eq!(san.get_esc(), DEFAULT_ESC);
+    assert_eq!(san.get_mode(), Mode::SYSTEM);
+
+    let san = Sanitizer::new().esc('~').mode(Mode::WINDOWS);
+    assert_eq!(san.get_esc(), '~');
+    assert_eq!(san.get_mode(), Mode::WINDOWS);
+
+    for name in ["", "hello", "a_b~c", "NUL.txt", "a:b. ", ".."] {
+        let res = san.escape(name);
+        assert_eq!(res, escape_str(name, '~', Mode::WINDOWS));
+        assert_eq!(san.unescape(&res), unescape_str(&res, '~'));
+        assert_eq!(san.unescape(&res), name);
+        assert_eq!(san.needs_escape(name), res != name);
+    }
+
+    let copy = san.clone();
+    assert_eq!(copy.mode(Mode::UNIX).escape("a:b"), "a:b");
+    assert_eq!(san.escape("a:b"), "a~3Ab");
+}
+
+#[test]
+fn lowercase_ext() {
+    let san = Sanitizer::new().mode(Mode::WINDOWS);
+    assert!(!san.get_lowercase_ext());
+    assert_eq!(san.escape("IMG_0001.JPG"), "IMG_5F0001.JPG");
+
+    let san = san.lowercase_ext(true);
+    assert!(san.get_lowercase_ext());
+    assert_eq!(san.escape("IMG_0001.JPG"), "IMG_5F0001.jpg");
+    assert_eq!(san.escape("A.TAR.GZ"), "A.TAR.gz");
+    assert_eq!(san.escape("NOEXT"), "NOEXT");
+    assert_eq!(san.escape(".HIDDEN"), ".HIDDEN");
+    assert_eq!(san.escape("CON.TXT"), "_43ON.txt");
+    assert_eq!(san.escape("A.TX:T "), "A.tx_3At_20");
+    assert_eq!(san.escape("A.TXT."), "A.TXT_2E");
+    assert_eq!(san.escape("A.ŽLUŤ"), "A.žluť");
+    assert_eq!(san.escape("A.ǅ"), "A.ǆ");
+    // Lowercase of `İ` is `i` followed by combining dot above.
+    assert_eq!(san.escape("A.İ"), "A.i\u{307}");
+    assert_eq!(
+        san.clone().mode(Mode::ASCII_ONLY).escape("A.İ"),
+        "A.i_u0307"
+    );
+
+    assert!(san.needs_escape("a.JPG"));
+    assert!(san.needs_escape("a.jp:g"));
+    assert!(!san.needs_escape("A.jpg"));
+    assert!(!Sanitizer::new().mode(Mode::WINDOWS).needs_escape("a.JPG"));
+}
+
+#[test]
+fn extra_reserved() {
+    let san = Sanitizer::new().mode(Mode::WINDOWS);
+    assert!(san.get_extra_reserved().is_empty());
+
+    let san = san.extra_reserved([".git", "Thumbs.db", "_x"]);
+    assert_eq!(
+        san.get_extra_reserved(),
+        [".git", "Thumbs.db", "_x"].map(Box::<str>::from)
+    );
+    assert_eq!(san.escape(".git"), "_2Egit");
+    assert_eq!(san.escape(".GIT.old"), "_2EGIT.old");
+    assert_eq!(san.escape("thumbs.db"), "_74humbs.db");
+    assert_eq!(san.escape("Thumbs.db:"), "Thumbs.db_3A");
+    assert_eq!(san.escape("Thumbs.db.:"), "_54humbs.db._3A");
+    assert_eq!(san.escape("_x"), "_5Fx");
+    assert_eq!(san.escape("NUL"), "_4EUL");
+    assert_eq!(san.escape(".gitignore"), ".gitignore");
+    assert_eq!(san.escape("a.git"), "a.git");
+    assert_eq!(san.escape(""), "");
+
+    for name in [".git", ".Git. ", "thumbs.db.txt", "_x", "a", "NUL.git"] {
+        let res = san.escape(name);
+        assert_eq!(san.unescape(&res), name);
+        assert_eq!(san.needs_escape(name), res != name);
+    }
+
+    // Names known only at runtime.
+    let names: Vec<String> =
+        "a.b,desktop".split(',').map(String::from).collect();
+    let san = san.extra_reserved(names);
+    assert_eq!(
+        san.get_extra_reserved(),
+        ["a.b", "desktop"].map(Box::<str>::from)
+    );
+    assert_eq!(san.escape("A.B.c"), "_41.B.c");
+    assert_eq!(san.escape("desktop.ini"), "_64esktop.ini");
+    assert_eq!(san.escape(".git"), ".git");
+}
+
+#[test]
+fn overrides() {
+    let san = Sanitizer::new().mode(Mode::MAC).disallow('#');
+    assert!(!san.allowed('#'));
+    assert!(!san.allowed(':'));
+    assert!(san.allowed('a'));
+    assert_eq!(san.escape("a#b"), "a_23b");
+    assert!(san.needs_escape("a#b"));
+    assert!(!san.needs_escape("a?b"));
+
+    let san = Sanitizer::new().mode(Mode::WINDOWS).allow(':').allow(' ');
+    assert_eq!(san.escape("C:a"), "C:a");
+    assert!(!san.needs_escape("C:a"));
+    // Reserved names and trailing characters are not affected.
+    assert_eq!(san.escape("a b "), "a b_20");
+    assert_eq!(san.escape("NUL.a:"), "_4EUL.a:");
+
+    // The last override wins.
+    let san = san.disallow(':');
+    assert_eq!(san.escape("C:a"), "C_3Aa");
+    assert_eq!(san.allow(':').escape("C:a"), "C:a");
+
+    // The escape character is always escaped.
+    let san = Sanitizer::new().mode(Mode::UNIX).allow('_').allow('/');
+    assert_eq!(san.escape("a_b/c"), "a_5Fb/c");
+
+    let san = Sanitizer::new().mode(Mode::POSIX).disallow('-').allow(' ');
+    for name in ["a-b c", "-", " ", "a.b", "ž"] {
+        let res = san.escape(name);
+        assert_eq!(san.unescape(&res), name);
+        assert_eq!(san.needs_escape(name), res != name);
+        assert!(res.chars().all(|c| c == '_' || san.allowed(c)), "{res:?}");
+    }
+}
+
+#[test]
+#[should_panic]
+fn override_non_ascii() {
+    _ = Sanitizer::new().allow('ž');
+}
+
+#[test]
+fn hex_case() {
+    let san = Sanitizer::new().mode(Mode::ALL);
+    assert_eq!(san.get_hex_case(), HexCase::Upper);
+    assert_eq!(san.escape("a:b"), "a_3Ab");
+
+    let san = san.hex_case(HexCase::Lower).extra_reserved(["Zz"]);
+    assert_eq!(san.get_hex_case(), HexCase::Lower);
+    assert_eq!(san.escape("a:b"), "a_3ab");
+    assert_eq!(san.escape("zz"), "_7az");
+    for name in ["a:b€", "nul.txt", "a\u{10FFFF}?", "..", "zz.:"] {
+        let res = san.escape(name);
+        assert_eq!(san.unescape(&res), name);
+        if name != "zz.:" {
+            let cased = escape_str_cased(name, '_', Mode::ALL, HexCase::Lower);
+            assert_eq!(res, cased);
+        }
+    }
+}
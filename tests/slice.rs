@@ -0,0 +1,36 @@
+use filesan::{BufferTooSmall, escape_str, escape_to_slice};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn slice() {
+    let mut buf = [0; 64];
+    for name in NAMES.iter().chain(&["€:\u{10FFFF}"]) {
+        for mode in modes() {
+            for esc in ['_', '€'] {
+                let res = escape_str(name, esc, mode);
+                let len = escape_to_slice(name, esc, mode, &mut buf).unwrap();
+                assert_eq!(&buf[..len], res.as_bytes());
+
+                let len = escape_to_slice(name, esc, mode, &mut buf[..len]);
+                assert_eq!(len, Ok(res.len()));
+
+                if !res.is_empty() {
+                    assert_eq!(
+                        escape_to_slice(
+                            name,
+                            esc,
+                            mode,
+                            &mut buf[..res.len() - 1]
+                        ),
+                        Err(BufferTooSmall {
+                            required: res.len()
+                        })
+                    );
+                }
+            }
+        }
+    }
+}
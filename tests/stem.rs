@@ -0,0 +1,86 @@
+use filesan::{
+    Mode, escape_stem_only, escape_str, split_escaped, unescape_str,
+};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn stem_only() {
+    let esc = |s| escape_stem_only(s, '_', Mode::WINDOWS);
+    assert_eq!(esc("song.mp3").unwrap(), "song.mp3");
+    assert_eq!(esc("a: b .mp3").unwrap(), "a_3A b .mp3");
+    assert_eq!(esc("CON.flac").unwrap(), "_43ON.flac");
+    assert_eq!(esc("con.tar.gz").unwrap(), "_63on.tar.gz");
+    assert_eq!(esc("a/b.c:d.flac").unwrap(), "a_2Fb.c_3Ad.flac");
+    assert_eq!(esc("a.").unwrap(), "a_2E");
+    assert_eq!(esc(".mp3").unwrap(), ".mp3");
+    assert_eq!(esc("noext").unwrap(), "noext");
+
+    let err = esc("song.mp:3").unwrap_err();
+    assert_eq!(err.extension(), "mp:3");
+    assert_eq!(err.to_string(), "Invalid extension `mp:3`.");
+    assert_eq!(esc("a.m_p3").unwrap_err().extension(), "m_p3");
+    assert_eq!(esc("a.mp3 ").unwrap_err().extension(), "mp3 ");
+    assert!(escape_stem_only("a.mp3 ", '_', Mode::UNIX).is_ok());
+    assert!(escape_stem_only("a.mp3", '.', Mode::UNIX).is_err());
+    assert!(escape_stem_only("a.ž", '_', Mode::ASCII_ONLY).is_err());
+
+    for name in NAMES {
+        for mode in modes() {
+            let Ok(res) = escape_stem_only(name, '_', mode) else {
+                continue;
+            };
+            assert_eq!(res, escape_str(name, '_', mode));
+            assert_eq!(unescape_str(&res, '_'), *name);
+            if let Some((_, ext)) = name.rsplit_once('.')
+                && !ext.is_empty()
+                && !name.starts_with('.')
+            {
+                assert!(res.ends_with(&format!(".{ext}")), "{name:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn split() {
+    assert_eq!(split_escaped("a.txt", '_'), ("a", Some("txt")));
+    assert_eq!(split_escaped("a.tar.gz", '_'), ("a.tar", Some("gz")));
+    assert_eq!(split_escaped("a_2Fb.txt", '_'), ("a_2Fb", Some("txt")));
+    assert_eq!(split_escaped("a.", '_'), ("a", Some("")));
+    assert_eq!(split_escaped(".txt", '_'), (".txt", None));
+    assert_eq!(split_escaped("", '_'), ("", None));
+
+    // The only dot is escaped.
+    assert_eq!(split_escaped("a_2E", '_'), ("a_2E", None));
+    assert_eq!(split_escaped("a_2Etxt", '_'), ("a_2Etxt", None));
+    assert_eq!(split_escaped("_2E.", '_'), ("_2E", Some("")));
+
+    // The name ends with escape sequence.
+    assert_eq!(split_escaped("a.tx_3A", '_'), ("a", Some("tx_3A")));
+    assert_eq!(split_escaped("a.b_u20AC", '_'), ("a", Some("b_u20AC")));
+    assert_eq!(split_escaped("a.txt_2E_20", '_'), ("a", Some("txt_2E_20")));
+
+    // Escape character is dot.
+    assert_eq!(split_escaped("a.2Eb", '.'), ("a.2Eb", None));
+    assert_eq!(split_escaped("a.2E.b", '.'), ("a.2E.b", None));
+    assert_eq!(split_escaped("a.b", '.'), ("a.b", None));
+
+    for name in NAMES.iter().chain(&["a.b.c:d", "x. .y", "a_.b", "._a"]) {
+        for mode in [Mode::UNIX, Mode::WINDOWS, Mode::ALL] {
+            for esc in ['_', '.'] {
+                let res = escape_str(name, esc, mode);
+                let (stem, ext) = split_escaped(&res, esc);
+                let stem = unescape_str(stem, esc);
+                match ext {
+                    Some(ext) => {
+                        assert_eq!(stem + "." + &unescape_str(ext, esc), *name)
+                    }
+                    None => assert_eq!(stem, *name),
+                }
+            }
+        }
+    }
+}
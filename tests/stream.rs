@@ -0,0 +1,176 @@
+use std::cell::Cell;
+
+use filesan::{EscapeChars, Mode, StreamEscaper, escape_chars, escape_str};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn chars() {
+    assert_eq!(
+        escape_chars("\x00hello/the_re.txt:.".chars(), '_', Mode::WINDOWS),
+        "_00hello_2Fthe_5Fre.txt_3A_2E"
+    );
+    assert_eq!(
+        escape_chars("NU".chars().chain("L.txt".chars()), '_', Mode::WINDOWS),
+        "_4EUL.txt"
+    );
+    assert_eq!(escape_chars([], '_', Mode::ALL), "");
+
+    for name in NAMES.iter().chain(&[
+        "con.a b.",
+        "lpt3.tx:t",
+        "conin$.txt",
+        "CONOUT$",
+        "CONOU",
+        "CLOCK$x",
+        "com¹.a",
+        "LPT³",
+    ]) {
+        for mode in modes() {
+            assert_eq!(
+                escape_chars(name.chars(), '_', mode),
+                escape_str(name, '_', mode)
+            );
+        }
+    }
+
+    const ALPHABET: &[char] = &[
+        'a', '.', ' ', '_', 'N', 'u', 'L', 'C', 'o', 'M', '1', '/', ':', 'ž',
+    ];
+    let mut names = vec![String::new()];
+    for _ in 0..4 {
+        names = names
+            .iter()
+            .flat_map(|n| ALPHABET.iter().map(move |c| format!("{n}{c}")))
+            .collect();
+        for mode in modes() {
+            for name in &names {
+                assert_eq!(
+                    escape_chars(name.chars(), '_', mode),
+                    escape_str(name, '_', mode),
+                    "{name:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn cloud() {
+    let names = [
+        " a",
+        "~$a.docx",
+        "~",
+        "~a",
+        ".lock",
+        ".LOCK.",
+        ".loc",
+        "desktop.ini",
+        "Desktop.INI",
+        "desktop",
+        "CON.txt",
+        " ",
+        "~$",
+    ];
+    for name in names {
+        for mode in [Mode::CLOUD, Mode::CLOUD | Mode::ALL] {
+            assert_eq!(
+                escape_chars(name.chars(), '_', mode),
+                escape_str(name, '_', mode),
+                "{name:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn escaper() {
+    let mut esc = StreamEscaper::new('_', Mode::WINDOWS);
+    esc.push_str("");
+    assert_eq!(esc.finish(), "");
+
+    let mut esc = StreamEscaper::new('_', Mode::WINDOWS);
+    esc.push_str("co");
+    esc.push_str("");
+    esc.push('m');
+    esc.push_str("1. .");
+    assert_eq!(esc.finish(), "_63om1_2E_20_2E");
+
+    // Every split of the names to 3 chunks.
+    for name in NAMES.iter().chain(&[
+        "con.a b.",
+        "lpt3.tx:t",
+        "a_b. ž€ .",
+        "auxiliary . ",
+    ]) {
+        let bounds: Vec<_> = name
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([name.len()])
+            .collect();
+        for mode in modes() {
+            let res = escape_str(name, '_', mode);
+            for (i, a) in bounds.iter().enumerate() {
+                for b in &bounds[i..] {
+                    let mut esc = StreamEscaper::new('_', mode);
+                    esc.push_str(&name[..*a]);
+                    esc.push_str(&name[*a..*b]);
+                    esc.push_str(&name[*b..]);
+                    assert_eq!(esc.finish(), res, "{name:?} {a} {b}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn iter() {
+    let res: String =
+        EscapeChars::new("nul.a:b".chars(), '_', Mode::WINDOWS).collect();
+    assert_eq!(res, "_6Eul.a_3Ab");
+    assert_eq!(EscapeChars::new([], '_', Mode::ALL).next(), None);
+
+    // The input is read lazily.
+    let res: String = EscapeChars::new("a:".chars().cycle(), '_', Mode::MAC)
+        .take(9)
+        .collect();
+    assert_eq!(res, "a_3Aa_3Aa");
+
+    let mut iter = EscapeChars::new("a.".chars(), '_', Mode::WINDOWS);
+    assert_eq!(iter.by_ref().collect::<String>(), "a_2E");
+    assert_eq!(iter.next(), None);
+
+    for name in NAMES.iter().chain(&[
+        "con.a b.",
+        "lpt3.tx:t",
+        "a_b. ž€ .",
+        "auxiliary . ",
+    ]) {
+        for mode in modes() {
+            let res: String =
+                EscapeChars::new(name.chars(), '_', mode).collect();
+            assert_eq!(res, escape_str(name, '_', mode), "{name:?}");
+        }
+    }
+}
+
+#[test]
+fn head_bound() {
+    for mode in (0..0x200).map(Mode::from_bits_retain) {
+        let bound = if mode.contains(Mode::CLOUD) { 11 } else { 7 };
+        for name in ["desktop.inia", "CONOUT$a", "conout$.txt", "COM¹a", "a"]
+        {
+            let read = Cell::new(0);
+            let mut iter = EscapeChars::new(
+                name.chars().cycle().inspect(|_| read.set(read.get() + 1)),
+                '_',
+                mode,
+            );
+            iter.next();
+            // One more character is read to decide about the buffered ones.
+            assert!(read.get() <= bound + 1, "{name:?} {mode}");
+        }
+    }
+}
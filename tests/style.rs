@@ -0,0 +1,106 @@
+use filesan::{
+    EscapeStyle, Mode, escape_str, escape_str_styled, unescape_styled,
+};
+
+mod common;
+
+use common::NAMES;
+
+const STYLES: &[EscapeStyle] = &[
+    EscapeStyle::HexSuffix,
+    EscapeStyle::Percent,
+    EscapeStyle::UnicodeBraces,
+];
+
+#[test]
+fn hex_suffix() {
+    assert_eq!(EscapeStyle::default(), EscapeStyle::HexSuffix);
+    for name in NAMES {
+        for mode in [Mode::UNIX, Mode::WINDOWS, Mode::ALL, Mode::ASCII_ONLY] {
+            for esc in ['_', '€'] {
+                assert_eq!(
+                    escape_str_styled(name, esc, mode, EscapeStyle::HexSuffix),
+                    escape_str(name, esc, mode)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn percent() {
+    let esc = |s, mode| escape_str_styled(s, '_', mode, EscapeStyle::Percent);
+    assert_eq!(esc("a/b%", Mode::UNIX), "a%2Fb%25");
+    assert_eq!(esc("a_b", Mode::UNIX), "a_b");
+    assert_eq!(esc("NUL.txt", Mode::WINDOWS), "%4EUL.txt");
+    assert_eq!(esc("a. ", Mode::WINDOWS), "a%2E%20");
+    assert_eq!(esc("..", Mode::UNIX), "%2E.");
+    assert_eq!(esc("ž€😀", Mode::ASCII_ONLY), "%C5%BE%E2%82%AC%F0%9F%98%80");
+
+    let un = |s| unescape_styled(s, '_', EscapeStyle::Percent);
+    assert_eq!(un("a%2fb%25"), "a/b%");
+    assert_eq!(un("a_2Fb"), "a_2Fb");
+    assert_eq!(un("%C5%BE%E2%82%AC"), "ž€");
+    assert_eq!(un("a%"), "a%");
+    assert_eq!(un("a%2"), "a%2");
+    assert_eq!(un("a%+F"), "a%+F");
+    assert_eq!(un("a%xy%41"), "a%xyA");
+    assert_eq!(un("%FF%41%C5"), "%FFA%C5");
+    assert_eq!(un("%C5%BE%C5"), "ž%C5");
+}
+
+#[test]
+fn unicode_braces() {
+    let esc =
+        |s, mode| escape_str_styled(s, '_', mode, EscapeStyle::UnicodeBraces);
+    assert_eq!(esc("a/b\\", Mode::UNIX), "a\\u{2F}b\\u{5C}");
+    assert_eq!(esc("nul. ", Mode::WINDOWS), "\\u{6E}ul\\u{2E}\\u{20}");
+    assert_eq!(esc("😀", Mode::ASCII_ONLY), "\\u{1F600}");
+
+    let un = |s| unescape_styled(s, '_', EscapeStyle::UnicodeBraces);
+    assert_eq!(un("a\\u{2f}b\\u{5C}"), "a/b\\");
+    assert_eq!(un("\\u{1F600}\\u{10FFFF}"), "😀\u{10FFFF}");
+    assert_eq!(un("\\u{}"), "\\u{}");
+    assert_eq!(un("\\u{1234567}"), "\\u{1234567}");
+    assert_eq!(un("\\u{D800}"), "\\u{D800}");
+    assert_eq!(un("\\u{+F}"), "\\u{+F}");
+    assert_eq!(un("\\u{41"), "\\u{41");
+    assert_eq!(un("\\x\\u{41}"), "\\xA");
+}
+
+#[test]
+fn remove() {
+    let esc = |s, mode| escape_str_styled(s, '_', mode, EscapeStyle::Remove);
+    assert_eq!(esc("a/b:c", Mode::WINDOWS), "abc");
+    assert_eq!(esc("a_b", Mode::WINDOWS), "a_b");
+    assert_eq!(esc("NUL.txt", Mode::WINDOWS), "UL.txt");
+    assert_eq!(esc("a. .", Mode::WINDOWS), "a");
+    assert_eq!(esc("..", Mode::ALL), "");
+    assert_eq!(esc("\0a", Mode::NONE), "\0a");
+    assert_eq!(esc("\0a", Mode::UNIX), "a");
+    assert_eq!(unescape_styled("a_2F", '_', EscapeStyle::Remove), "a_2F");
+}
+
+#[test]
+fn round_trip() {
+    for name in NAMES {
+        for mode in [
+            Mode::NONE,
+            Mode::UNIX,
+            Mode::WINDOWS,
+            Mode::ALL,
+            Mode::ASCII_ONLY | Mode::ALL,
+        ] {
+            for (style, esc) in
+                STYLES.iter().flat_map(|s| [(s, '_'), (s, '€')])
+            {
+                let res = escape_str_styled(name, esc, mode, *style);
+                assert_eq!(
+                    unescape_styled(&res, esc, *style),
+                    *name,
+                    "{style:?} {esc:?}"
+                );
+            }
+        }
+    }
+}
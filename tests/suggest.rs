@@ -0,0 +1,72 @@
+use filesan::{Mode, needs_escape, slugify, suggest};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn suggestions() {
+    assert_eq!(suggest("report.", Mode::WINDOWS), "report");
+    assert_eq!(suggest("report. . ", Mode::WINDOWS), "report");
+    assert_eq!(suggest("report.", Mode::UNIX), "report.");
+    assert_eq!(suggest("a:b?.txt", Mode::WINDOWS), "a-b-.txt");
+    assert_eq!(suggest("a:b", Mode::UNIX), "a:b");
+    assert_eq!(suggest("a/b", Mode::UNIX), "a-b");
+    assert_eq!(suggest("NUL", Mode::WINDOWS), "NUL_file");
+    assert_eq!(suggest("NUL.", Mode::WINDOWS), "NUL_file");
+    assert_eq!(suggest("nul.txt", Mode::WINDOWS), "nul_file.txt");
+    assert_eq!(suggest(".", Mode::UNIX), "._file");
+    assert_eq!(suggest("..", Mode::ALL), "file");
+    assert_eq!(suggest("...", Mode::UNIX), "...");
+    assert_eq!(suggest("-rf", Mode::POSIX), "file-rf");
+    assert_eq!(suggest("a b", Mode::POSIX), "a-b");
+    assert_eq!(suggest("ž", Mode::ASCII_ONLY), "-");
+    assert_eq!(suggest("", Mode::NONE), "file");
+    assert_eq!(suggest(" a", Mode::CLOUD), "file a");
+    assert_eq!(suggest("~$a.docx", Mode::CLOUD), "file~$a.docx");
+    assert_eq!(suggest(".lock", Mode::CLOUD), ".lock_file");
+    assert_eq!(suggest("a:b/c", Mode::HFS), "a-b/c");
+
+    for name in NAMES {
+        for mode in modes() {
+            let res = suggest(name, mode);
+            assert!(!res.is_empty());
+            assert!(
+                !needs_escape(&res, '\u{10FFFF}', mode),
+                "{name:?} {mode:?} {res:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn slugs() {
+    assert_eq!(
+        slugify("Meeting Notes: Q3/Q4 – Draft?.docx", Mode::WINDOWS),
+        "Meeting-Notes-Q3-Q4-Draft.docx"
+    );
+    assert_eq!(slugify("  a  -- b  ", Mode::UNIX), "a-b");
+    assert_eq!(slugify("a b. txt ", Mode::UNIX), "a-b.txt");
+    assert_eq!(slugify("a_b.tar.gz", Mode::UNIX), "a_b.tar.gz");
+    assert_eq!(slugify("report.", Mode::WINDOWS), "report");
+    assert_eq!(slugify("a:b", Mode::UNIX), "a:b");
+    assert_eq!(slugify("a:b", Mode::MAC), "a-b");
+    assert_eq!(slugify("con.docx", Mode::WINDOWS), "con_file.docx");
+    assert_eq!(slugify("CON", Mode::WINDOWS), "CON_file");
+    assert_eq!(slugify("???", Mode::WINDOWS), "file");
+    assert_eq!(slugify("-rf", Mode::POSIX), "rf");
+    assert_eq!(slugify("žluťoučký kůň.txt", Mode::POSIX), "lu-ou-k-k.txt");
+    assert_eq!(slugify(" ~$a b.docx", Mode::CLOUD), "file~$a-b.docx");
+    assert_eq!(slugify("desktop.ini", Mode::CLOUD), "desktop.ini_file");
+
+    for name in NAMES {
+        for mode in modes() {
+            let res = slugify(name, mode);
+            assert!(!res.is_empty());
+            assert!(
+                !needs_escape(&res, '\u{10FFFF}', mode),
+                "{name:?} {mode:?} {res:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "translit")]
+
+use filesan::{
+    Mode, escape_str, escape_str_translit, needs_escape, transliterate,
+    unescape_str,
+};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn translit() {
+    assert_eq!(transliterate("é"), "e");
+    assert_eq!(transliterate("ß"), "ss");
+    assert_eq!(transliterate("Ærøskøbing"), "AEroskobing");
+    assert_eq!(transliterate("Łódź"), "Lodz");
+    assert_eq!(transliterate("ÀÿĀſ"), "AyAs");
+    assert_eq!(transliterate("ĲsselMeer"), "IJsselMeer");
+    assert_eq!(transliterate("a – b—c"), "a - b-c");
+    assert_eq!(transliterate("“a” ‘b’…"), "\"a\" 'b'...");
+    assert_eq!(transliterate("a\u{A0}b"), "a b");
+
+    // Combining characters
+    assert_eq!(transliterate("e\u{301}"), "e");
+    assert_eq!(transliterate("a\u{308}\u{304}"), "a");
+
+    // CJK and symbols are kept.
+    assert_eq!(transliterate("漢字"), "漢字");
+    assert_eq!(transliterate("☃"), "☃");
+}
+
+#[test]
+fn escape() {
+    let esc = |s, mode| escape_str_translit(s, '_', mode);
+    assert_eq!(esc("Café: ☃.txt", Mode::WINDOWS), "Cafe_3A _u2603.txt");
+    assert_eq!(esc("漢字", Mode::UNIX), "_u6F22_u5B57");
+    assert_eq!(esc("ÑUL.txt", Mode::WINDOWS), "_4EUL.txt");
+    assert_eq!(esc("a\u{A0}", Mode::WINDOWS), "a_20");
+    assert_eq!(esc("a – b", Mode::POSIX), "a_20-_20b");
+
+    // Lossy: different names give the same result.
+    assert_eq!(esc("é", Mode::UNIX), esc("e\u{301}", Mode::UNIX));
+    assert_eq!(esc("é", Mode::UNIX), esc("e", Mode::UNIX));
+    assert_eq!(unescape_str(&esc("é☃", Mode::UNIX), '_'), "e☃");
+
+    for name in NAMES {
+        for mode in modes() {
+            let res = esc(name, mode);
+            assert!(res.is_ascii(), "{res:?}");
+            assert!(!needs_escape(&res, '\u{10FFFF}', mode), "{res:?}");
+            let ascii = mode | Mode::ASCII_ONLY;
+            assert_eq!(res, escape_str(&transliterate(name), '_', ascii));
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use filesan::{
+    Mode, escape_str, escape_truncated, escape_truncated_bytes,
+    escape_truncated_unique, escape_truncated_utf16, needs_escape,
+    unescape_str,
+};
+
+mod common;
+
+use common::{NAMES, modes};
+use std::collections::HashSet;
+
+#[test]
+fn truncated() {
+    let tr = |s, max| escape_truncated(s, '_', Mode::WINDOWS, max);
+    assert_eq!(tr("hello.txt", 100), "hello.txt");
+    assert_eq!(tr("hello.txt", 9), "hello.txt");
+    assert_eq!(tr("hello.txt", 8), "hell.txt");
+    assert_eq!(tr("hello.txt", 5), "h.txt");
+    assert_eq!(tr("hello.txt", 4), "hell");
+    assert_eq!(tr("hello.txt", 0), "");
+    assert_eq!(tr("a:b.txt", 6), "a.txt");
+    assert_eq!(tr("a:b.txt", 8), "a_3A.txt");
+    assert_eq!(tr("nulx.txt", 7), "nu.txt");
+    assert_eq!(tr("nul:.txt", 9), "_6Eul.txt");
+    assert_eq!(tr("ab .txt", 6), "ab.txt");
+    assert_eq!(tr("ab .", 3), "ab");
+    assert_eq!(tr(".bashrc", 4), ".bas");
+    assert_eq!(tr("žžž.ž", 4), "žž.ž");
+
+    for name in NAMES {
+        for mode in modes() {
+            let full = escape_str(name, '_', mode);
+            for max in 0..full.chars().count() + 2 {
+                let res = escape_truncated(name, '_', mode, max);
+                assert!(res.chars().count() <= max);
+                // The result is escaped truncated name.
+                let orig = unescape_str(&res, '_');
+                assert_eq!(escape_str(&orig, '_', mode), res);
+                if full.chars().count() <= max {
+                    assert_eq!(res, full);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn truncated_bytes() {
+    let tr = |s, max| escape_truncated_bytes(s, '_', Mode::WINDOWS, max);
+    assert_eq!(tr("hello.txt", 8), "hell.txt");
+    assert_eq!(tr("žžž.txt", 9), "žž.txt");
+    assert_eq!(tr("žžž.txt", 8), "žž.txt");
+    assert_eq!(tr("žžž.txt", 7), "ž.txt");
+    assert_eq!(tr("žžž.ž", 5), "ž.ž");
+    assert_eq!(tr("€:.txt", 7), "€.txt");
+    assert_eq!(tr("a:b", 3), "a");
+    assert_eq!(tr("€", 2), "");
+
+    for name in NAMES {
+        for mode in modes() {
+            for esc in ['_', 'é'] {
+                let full = escape_str(name, esc, mode);
+                for max in 0..full.len() + 2 {
+                    let res = escape_truncated_bytes(name, esc, mode, max);
+                    assert!(res.len() <= max);
+                    let orig = unescape_str(&res, esc);
+                    assert_eq!(escape_str(&orig, esc, mode), res);
+                    if full.len() <= max {
+                        assert_eq!(res, full);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn truncated_utf16() {
+    let tr = |s: &str, max| escape_truncated_utf16(s, '_', Mode::FAT, max);
+    let units = |s: &str| s.encode_utf16().count();
+    assert_eq!(tr("hello.txt", 8), "hell.txt");
+    assert_eq!(tr("žžž.txt", 6), "žž.txt");
+    assert_eq!(tr("😀😀😀.txt", 8), "😀😀.txt");
+    assert_eq!(tr("😀😀😀.txt", 7), "😀.txt");
+    assert_eq!(tr("😀", 1), "");
+    assert_eq!(tr("a:b", 3), "a");
+    assert_eq!(units(&tr(&"a".repeat(300), 255)), 255);
+    assert_eq!(units(&tr(&"😀".repeat(300), 255)), 254);
+
+    for name in NAMES.iter().chain(&["😀😀.😀"]) {
+        for mode in modes() {
+            let full = escape_str(name, '_', mode);
+            for max in 0..full.len() + 2 {
+                let res = escape_truncated_utf16(name, '_', mode, max);
+                assert!(units(&res) <= max);
+                let orig = unescape_str(&res, '_');
+                assert_eq!(escape_str(&orig, '_', mode), res);
+                if units(&full) <= max {
+                    assert_eq!(res, full);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn truncated_unique() {
+    let tr = |s, max| escape_truncated_unique(s, '_', Mode::WINDOWS, max);
+    assert_eq!(tr("hello.txt", 9), "hello.txt");
+    assert_eq!(tr("", 0), "");
+
+    let res = tr("hello world.txt", 13);
+    assert_eq!(res.chars().count(), 13);
+    assert!(res.starts_with("_-"));
+    assert!(res.ends_with(".txt"));
+
+    let res = tr("hello world long.", 18);
+    assert!(res.starts_with("hello _-"), "{res}");
+    assert!(res.ends_with("_2E"));
+
+    let res = tr("hello world", 10);
+    assert!(res.starts_with("h_-"));
+    // Not even the hash fits.
+    assert_eq!(tr("hello world.txt", 8), "hell.txt");
+
+    // Different names don't collide.
+    for max in [9, 10, 15, 20] {
+        let mut res = HashSet::new();
+        for i in 0..1000 {
+            let name = format!("long name number {i}.txt");
+            let r = escape_truncated_unique(&name, '_', Mode::ALL, max);
+            assert!(r.chars().count() <= max);
+            assert_eq!(r.ends_with(".txt"), max >= 13);
+            assert!(res.insert(r));
+        }
+        for name in NAMES {
+            let r = escape_truncated_unique(name, '_', Mode::ALL, max);
+            assert!(r.chars().count() <= max);
+            assert!(res.insert(r));
+        }
+    }
+
+    for name in NAMES {
+        for mode in modes() {
+            for esc in ['_', 'a'] {
+                let full = escape_str(name, esc, mode);
+                for max in 0..full.chars().count() + 2 {
+                    let res = escape_truncated_unique(name, esc, mode, max);
+                    assert!(res.chars().count() <= max);
+                    assert!(
+                        !needs_escape(&res, '\u{10FFFF}', mode),
+                        "{name:?} {mode} {res:?}"
+                    );
+                    if full.chars().count() <= max {
+                        assert_eq!(res, full);
+                    } else if max >= 9 {
+                        assert!(res.contains(&format!("{esc}-")));
+                    }
+                }
+            }
+        }
+    }
+
+    let res =
+        escape_truncated_unique("a_ver_long_name.txt", '_', Mode::POSIX, 20);
+    assert!(res.starts_with("a_5Fver_-"), "{res}");
+    assert!(!needs_escape(&res, '\u{10FFFF}', Mode::POSIX));
+    let res = escape_truncated_unique("banana split.txt", 'a', Mode::ALL, 17);
+    assert!(res.starts_with("ba61a-"), "{res}");
+    assert!(res.ends_with(".txt"));
+}
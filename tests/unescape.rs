@@ -0,0 +1,271 @@
+use filesan::{
+    HexCase, Mode, UnescapeError, escape_str, escape_str_cased, is_escaped,
+    try_unescape_str, unescape_iter, unescape_lossy, unescape_str,
+};
+
+mod common;
+
+use common::{NAMES, modes};
+
+#[test]
+fn unescape() {
+    assert_eq!(
+        unescape_str("_00hello_2Fthe_5Fre.txt_3A_2E", '_'),
+        "\x00hello/the_re.txt:."
+    );
+    assert_eq!(unescape_str("_4EUL.txt", '_'), "NUL.txt");
+    assert_eq!(unescape_str("_2E.", '_'), "..");
+
+    // Invalid escapes are kept.
+    assert_eq!(unescape_str("a_", '_'), "a_");
+    assert_eq!(unescape_str("a_2", '_'), "a_2");
+    assert_eq!(unescape_str("a_xy_41", '_'), "a_xyA");
+    assert_eq!(unescape_str("_+F", '_'), "_+F");
+    assert_eq!(unescape_str("_2ž", '_'), "_2ž");
+
+    assert_eq!(unescape_str("_u017Elu_u0165", '_'), "žluť");
+    assert_eq!(unescape_str("_U01F600_u20ac", '_'), "😀€");
+    assert_eq!(unescape_str("_u01", '_'), "_u01");
+    assert_eq!(unescape_str("_uD800", '_'), "_uD800");
+    assert_eq!(unescape_str("_U110000", '_'), "_U110000");
+}
+
+#[test]
+fn round_trip() {
+    for mode in [
+        Mode::NONE,
+        Mode::UNIX,
+        Mode::WINDOWS,
+        Mode::MAC,
+        Mode::ALL,
+        Mode::ASCII_ONLY,
+        Mode::ASCII_ONLY | Mode::ALL,
+    ] {
+        for name in NAMES {
+            for esc in ['_', '~', 'é', '€', '😀'] {
+                let escaped = escape_str(name, esc, mode);
+                assert_eq!(unescape_str(&escaped, esc), *name);
+            }
+        }
+    }
+}
+
+#[test]
+fn format() {
+    for (c, e) in [
+        ('\u{0}', "_00"),
+        ('\u{FF}', "_FF"),
+        ('\u{100}', "_u0100"),
+        ('\u{FFFF}', "_uFFFF"),
+        ('\u{10000}', "_U010000"),
+        ('\u{10FFFF}', "_U10FFFF"),
+    ] {
+        let s = c.to_string();
+        assert_eq!(escape_str(&s, '_', Mode::ASCII_ONLY | Mode::UNIX), e);
+        assert_eq!(unescape_str(e, '_'), s);
+        let lower: String = e
+            .chars()
+            .map(|c| {
+                if c.is_ascii_hexdigit() {
+                    c.to_ascii_lowercase()
+                } else {
+                    c
+                }
+            })
+            .collect();
+        assert_eq!(unescape_str(&lower, '_'), s);
+    }
+
+    assert_eq!(unescape_str("_2f_u00e9_U01f600", '_'), "/é😀");
+    assert_eq!(unescape_str("_u12345", '_'), "\u{1234}5");
+    assert_eq!(unescape_str("_Uffff", '_'), "_Uffff");
+    assert_eq!(unescape_str("_UFFFFFF", '_'), "_UFFFFFF");
+}
+
+#[test]
+fn try_unescape() {
+    assert_eq!(
+        try_unescape_str("_00hello_2Fthe_5Fre.txt_3A_2E", '_').unwrap(),
+        "\x00hello/the_re.txt:."
+    );
+    assert_eq!(try_unescape_str("", '_').unwrap(), "");
+
+    assert_eq!(
+        try_unescape_str("a_", '_'),
+        Err(UnescapeError::TruncatedEscape(1))
+    );
+    assert_eq!(
+        try_unescape_str("ž_2", '_'),
+        Err(UnescapeError::TruncatedEscape(2))
+    );
+    assert_eq!(
+        try_unescape_str("_41_xy", '_'),
+        Err(UnescapeError::InvalidHexDigit(3))
+    );
+    assert_eq!(
+        try_unescape_str("é4x", 'é'),
+        Err(UnescapeError::InvalidHexDigit(0))
+    );
+    assert_eq!(try_unescape_str("_+F", '_').unwrap_err().offset(), 0);
+    assert_eq!(try_unescape_str("a€u20AC", '€').unwrap(), "a€");
+    assert_eq!(
+        try_unescape_str("ab_u12", '_'),
+        Err(UnescapeError::TruncatedEscape(2))
+    );
+    assert_eq!(
+        try_unescape_str("_U01F60x", '_'),
+        Err(UnescapeError::InvalidHexDigit(0))
+    );
+    assert_eq!(
+        try_unescape_str("a_uDFFF", '_'),
+        Err(UnescapeError::InvalidCodePoint(1))
+    );
+    assert_eq!(
+        try_unescape_str("_U110000", '_'),
+        Err(UnescapeError::InvalidCodePoint(0))
+    );
+
+    for mode in [Mode::NONE, Mode::UNIX, Mode::WINDOWS, Mode::MAC, Mode::ALL] {
+        for name in NAMES.iter().chain(&["€", "a€b€u20AC"]) {
+            for esc in ['_', '€'] {
+                let escaped = escape_str(name, esc, mode);
+                assert_eq!(try_unescape_str(&escaped, esc).unwrap(), *name);
+            }
+        }
+    }
+}
+
+#[test]
+fn lossy() {
+    assert_eq!(unescape_lossy("_4EUL_2Etxt", '_'), "NUL.txt");
+    assert_eq!(unescape_lossy("", '_'), "");
+
+    // Truncated at the end
+    assert_eq!(unescape_lossy("a_", '_'), "a_");
+    assert_eq!(unescape_lossy("a_4", '_'), "a_4");
+    assert_eq!(unescape_lossy("a_u20A", '_'), "a_u20A");
+    assert_eq!(unescape_lossy("a_U01F60", '_'), "a_U01F60");
+
+    // Not hex digits
+    assert_eq!(unescape_lossy("my_name_2F", '_'), "my_name/");
+    assert_eq!(unescape_lossy("_+F_u12x4", '_'), "_+F_u12x4");
+
+    // Invalid code points
+    assert_eq!(unescape_lossy("a_uD800b", '_'), "a\u{FFFD}b");
+    assert_eq!(unescape_lossy("_uDFFF_41", '_'), "\u{FFFD}A");
+    assert_eq!(unescape_lossy("_U110000", '_'), "\u{FFFD}");
+    assert_eq!(unescape_lossy("_UFFFFFF_", '_'), "\u{FFFD}_");
+    assert_eq!(unescape_lossy("éuD800é41", 'é'), "\u{FFFD}A");
+
+    for name in NAMES.iter().chain(&["a_", "my_name"]) {
+        assert_eq!(unescape_lossy(name, '_'), unescape_str(name, '_'));
+        for mode in modes() {
+            let escaped = escape_str(name, '_', mode);
+            assert_eq!(unescape_lossy(&escaped, '_'), *name);
+        }
+    }
+}
+
+#[test]
+fn iter() {
+    let collect =
+        |s, esc| unescape_iter(s, esc).collect::<Result<String, _>>();
+    assert_eq!(collect("_4EUL_2Etxt", '_').unwrap(), "NUL.txt");
+    assert_eq!(collect("a_u017E_U01F600", '_').unwrap(), "až😀");
+    assert_eq!(collect("€u20AC€41", '€').unwrap(), "€A");
+    assert_eq!(collect("", '_').unwrap(), "");
+
+    let items: Vec<_> = unescape_iter("a_2Fb_u12x4c", '_').collect();
+    assert_eq!(
+        items,
+        [
+            Ok('a'),
+            Ok('/'),
+            Ok('b'),
+            Err(UnescapeError::InvalidHexDigit(5))
+        ]
+    );
+    let mut iter = unescape_iter("_uD800a", '_');
+    assert_eq!(iter.next(), Some(Err(UnescapeError::InvalidCodePoint(0))));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+
+    // Malformed input ends the iterator early.
+    for s in [format!("_zz{}", "a".repeat(99)), "a_".into(), String::new()] {
+        let iter = unescape_iter(&s, '_');
+        let (lo, hi) = iter.size_hint();
+        let count = iter.count();
+        assert!(lo <= count && count <= hi.unwrap(), "{s:?}");
+    }
+    assert_eq!(unescape_iter("_zza", '_').size_hint(), (1, Some(4)));
+    assert_eq!(unescape_iter("", '_').size_hint(), (0, Some(0)));
+
+    for mode in modes() {
+        for name in NAMES.iter().chain(&["€", "a€b€u20AC", "😀"]) {
+            for esc in ['_', '€'] {
+                let escaped = escape_str(name, esc, mode);
+                let iter = unescape_iter(&escaped, esc);
+                let (lo, hi) = iter.size_hint();
+                let res: Vec<_> = iter.map(Result::unwrap).collect();
+                assert!(lo <= res.len() && res.len() <= hi.unwrap());
+                assert_eq!(
+                    String::from_iter(res),
+                    unescape_str(&escaped, esc)
+                );
+            }
+        }
+        for name in NAMES.iter().chain(&["a_", "my_name", "_uD800"]) {
+            assert_eq!(collect(name, '_'), try_unescape_str(name, '_'));
+        }
+    }
+}
+
+#[test]
+fn escaped() {
+    for name in NAMES {
+        for mode in modes() {
+            let res = escape_str(name, '_', mode);
+            assert!(is_escaped(&res, '_', mode), "{res:?} {mode:?}");
+            let res = escape_str_cased(name, '_', mode, HexCase::Lower);
+            assert!(is_escaped(&res, '_', mode), "{res:?} {mode:?}");
+        }
+    }
+
+    // Possible false positives.
+    for (s, mode) in [
+        ("_", Mode::NONE),
+        ("a_", Mode::NONE),
+        ("_2", Mode::NONE),
+        ("_XY", Mode::NONE),
+        ("_2g", Mode::NONE),
+        ("_41", Mode::NONE),
+        ("_3A", Mode::UNIX),
+        ("_3a", Mode::UNIX),
+        ("_2f_3A", Mode::ALL),
+        ("a/b", Mode::UNIX),
+        ("NUL", Mode::WINDOWS),
+        ("nul.txt", Mode::WINDOWS),
+        ("a.", Mode::WINDOWS),
+        ("a_2E.", Mode::WINDOWS),
+        ("..", Mode::UNIX),
+        ("a__5F", Mode::NONE),
+    ] {
+        assert!(!is_escaped(s, '_', mode), "{s:?} {mode:?}");
+    }
+
+    for (s, mode) in [
+        ("", Mode::ALL),
+        ("hello.txt", Mode::ALL),
+        ("_2F", Mode::UNIX),
+        ("_2f", Mode::UNIX),
+        ("_2f_3a", Mode::ALL),
+        ("_5f_5f", Mode::NONE),
+        ("_5F_5F", Mode::NONE),
+        ("a:b", Mode::UNIX),
+        ("_4EUL", Mode::WINDOWS),
+        ("_2E.", Mode::UNIX),
+        ("a_2E", Mode::WINDOWS),
+    ] {
+        assert!(is_escaped(s, '_', mode), "{s:?} {mode:?}");
+    }
+}
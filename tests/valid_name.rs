@@ -0,0 +1,64 @@
+use filesan::{IssueKind, Mode, ValidName, escape_str};
+
+mod common;
+
+use common::{NAMES, modes};
+
+/// Takes the name and returns the string borrowed from the original.
+fn stem<'a>(name: ValidName<'a>) -> &'a str {
+    name.as_str().split('.').next().unwrap()
+}
+
+#[test]
+fn valid_name() {
+    let owned = String::from("report.tar.gz");
+    let res = {
+        let name = ValidName::new(&owned, Mode::ALL).unwrap();
+        let copy = name;
+        assert_eq!(name, copy);
+        assert_eq!(name.to_string(), "report.tar.gz");
+        assert_eq!(name.len(), 13);
+        stem(name)
+    };
+    assert_eq!(res, "report");
+
+    let names: Vec<ValidName> = ["a", "b.txt"]
+        .iter()
+        .map(|n| ValidName::new(n, Mode::WINDOWS).unwrap())
+        .collect();
+    assert_eq!(names[1].as_ref(), "b.txt");
+
+    let name: ValidName = "a.txt".try_into().unwrap();
+    assert_eq!(name.mode(), Mode::SYSTEM);
+
+    let issues = ValidName::new("a:b.", Mode::WINDOWS).unwrap_err();
+    let kinds: Vec<_> = issues.iter().map(|i| i.kind).collect();
+    assert_eq!(kinds, [IssueKind::Disallowed(':'), IssueKind::Trailing]);
+    assert_eq!(ValidName::try_from("").unwrap().as_str(), "");
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn path_component() {
+    use std::path::Component;
+
+    let name = ValidName::new("a b", Mode::SYSTEM).unwrap();
+    assert_eq!(
+        name.as_path_component(),
+        Some(Component::Normal("a b".as_ref()))
+    );
+    for name in [".", "..", "a/b", "a/", "/"] {
+        let name = ValidName::new(name, Mode::NONE).unwrap();
+        assert_eq!(name.as_path_component(), None, "{name:?}");
+    }
+}
+
+#[test]
+fn escaped() {
+    for mode in modes() {
+        for name in NAMES {
+            let esc = escape_str(name, '_', mode);
+            assert!(ValidName::new(&esc, mode).is_ok(), "{esc:?} {mode}");
+        }
+    }
+}
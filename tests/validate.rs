@@ -0,0 +1,195 @@
+use filesan::{
+    Issue, IssueKind, Mode, ValidationError, escape_str, needs_escape,
+    validate, validate_issues, validate_path_issues,
+};
+
+mod common;
+
+use common::{NAMES, UNUSED_ESC, modes};
+
+#[test]
+fn validation() {
+    assert_eq!(validate("hello.txt", Mode::ALL), Ok(()));
+    assert_eq!(validate("", Mode::ALL), Ok(()));
+    assert_eq!(validate("a:b", Mode::UNIX), Ok(()));
+
+    let err = validate("NUL.a:b?.", Mode::WINDOWS).unwrap_err();
+    assert_eq!(
+        err,
+        ValidationError {
+            invalid: vec![(5, ':'), (7, '?')],
+            reserved: true,
+            trailing: true,
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "Invalid name: disallowed character ':' at 5, disallowed character \
+         '?' at 7, reserved name, disallowed characters at the end."
+    );
+
+    let err = validate("..", Mode::UNIX).unwrap_err();
+    assert!(err.invalid.is_empty() && err.reserved && !err.trailing);
+    assert_eq!(err.to_string(), "Invalid name: reserved name.");
+
+    let err = validate("a ", Mode::WINDOWS).unwrap_err();
+    assert!(err.invalid.is_empty() && !err.reserved && err.trailing);
+
+    let err = validate("ž/", Mode::UNIX).unwrap_err();
+    assert_eq!(err.invalid, [(2, '/')]);
+
+    for name in NAMES {
+        for mode in modes() {
+            // There is no escape character in the names.
+            assert_eq!(
+                validate(name, mode).is_ok(),
+                !needs_escape(name, UNUSED_ESC, mode),
+                "{name:?} {mode:?}"
+            );
+            assert_eq!(validate(&escape_str(name, '_', mode), mode), Ok(()));
+        }
+    }
+}
+
+#[test]
+fn issues() {
+    let issue = |kind, span, systems| Issue {
+        kind,
+        span,
+        systems,
+    };
+    const W: Mode = Mode::WINDOWS;
+    assert_eq!(validate_issues("hello.txt", Mode::ALL), Ok(()));
+    assert_eq!(
+        validate_issues("", Mode::UNIX_MAC),
+        Err(vec![issue(IssueKind::Empty, 0..0, Mode::UNIX_MAC)])
+    );
+
+    // Every category at once.
+    assert_eq!(
+        validate_issues("NUL.ž:b?. ", Mode::ALL).unwrap_err(),
+        [
+            issue(IssueKind::Reserved, 0..3, W),
+            issue(IssueKind::Disallowed(':'), 6..7, Mode::WINDOWS_MAC),
+            issue(IssueKind::Disallowed('?'), 8..9, W),
+            issue(IssueKind::Trailing, 9..11, W),
+        ]
+    );
+    let mode = Mode::POSIX | Mode::WINDOWS;
+    assert_eq!(
+        validate_issues("-ž .", mode).unwrap_err(),
+        [
+            issue(IssueKind::Reserved, 0..1, Mode::POSIX),
+            issue(IssueKind::Disallowed('ž'), 1..3, Mode::POSIX),
+            issue(IssueKind::Disallowed(' '), 3..4, Mode::POSIX),
+            issue(IssueKind::Trailing, 3..5, W),
+        ]
+    );
+    assert_eq!(
+        validate_issues("..", Mode::ALL).unwrap_err(),
+        [
+            issue(IssueKind::Reserved, 0..2, Mode::UNIX_MAC),
+            issue(IssueKind::Trailing, 0..2, W),
+        ]
+    );
+
+    for name in NAMES {
+        for mode in modes() {
+            let issues = validate_issues(name, mode);
+            if name.is_empty() {
+                assert!(issues.is_err());
+                continue;
+            }
+            assert_eq!(issues.is_ok(), validate(name, mode).is_ok());
+            if issues.is_ok() {
+                assert_eq!(escape_str(name, UNUSED_ESC, mode), *name);
+            }
+            for i in issues.err().unwrap_or_default() {
+                assert!(mode.contains(i.systems) && !i.systems.is_empty());
+            }
+        }
+    }
+}
+
+#[test]
+fn messages() {
+    let messages = |s, mode| {
+        validate_issues(s, mode)
+            .unwrap_err()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        messages("my file:a", Mode::ALL),
+        ["character ':' at byte 7 is not allowed on Windows or macOS"]
+    );
+    assert_eq!(
+        messages("NUL.a/\x01. ", Mode::ALL),
+        [
+            "the name is reserved on Windows",
+            "character '/' at byte 5 is not allowed on Unix, Windows or macOS",
+            "character '\\u{1}' at byte 6 is not allowed on Windows",
+            "characters at bytes 7..9 are not allowed at the end of the name \
+             on Windows",
+        ]
+    );
+    assert_eq!(
+        messages("-ž", Mode::ALL | Mode::POSIX | Mode::ASCII_ONLY),
+        [
+            "the name is reserved on POSIX",
+            "character 'ž' at byte 1 is not allowed on POSIX or ASCII only",
+        ]
+    );
+    assert_eq!(
+        messages(".", Mode::UNIX | Mode::WINDOWS_END),
+        [
+            "the name is reserved on Unix",
+            "character '.' at byte 0 is not allowed on Windows",
+        ]
+    );
+    assert_eq!(messages("", Mode::ALL), ["the name is empty"]);
+}
+
+#[test]
+fn path_issues() {
+    assert_eq!(validate_path_issues("a/b/c.txt", Mode::ALL), Ok(()));
+    assert_eq!(validate_path_issues("/a//b/", Mode::ALL), Ok(()));
+
+    let issues =
+        validate_path_issues("a/b:c\\NUL.", Mode::WINDOWS).unwrap_err();
+    let found: Vec<_> =
+        issues.iter().map(|i| (i.kind, i.span.clone())).collect();
+    assert_eq!(
+        found,
+        [
+            (IssueKind::Disallowed(':'), 3..4),
+            (IssueKind::Reserved, 6..9),
+            (IssueKind::Trailing, 9..10),
+        ]
+    );
+    assert_eq!(
+        issues[0].to_string(),
+        "character ':' at byte 3 is not allowed on Windows"
+    );
+
+    // `\` is separator only on Windows.
+    assert_eq!(validate_path_issues("a\\..", Mode::UNIX), Ok(()));
+    let issues =
+        validate_path_issues("a\\..", Mode::UNIX_WINDOWS).unwrap_err();
+    assert_eq!(issues[0].kind, IssueKind::Reserved);
+    assert_eq!(issues[0].span, 2..4);
+    assert_eq!(
+        validate_path_issues("", Mode::ALL).unwrap_err()[0].kind,
+        IssueKind::Empty
+    );
+
+    for mode in modes() {
+        for name in NAMES.iter().filter(|n| !n.contains(['/', '\\'])) {
+            assert_eq!(
+                validate_path_issues(name, mode),
+                validate_issues(name, mode)
+            );
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use filesan::{DisallowedReason, Mode, allowed, why_disallowed};
+
+#[test]
+fn reasons() {
+    use DisallowedReason::*;
+    assert_eq!(
+        why_disallowed('\x07', Mode::ALL),
+        Some((Control, Mode::WINDOWS))
+    );
+    assert_eq!(why_disallowed('/', Mode::ALL), Some((Separator, Mode::ALL)));
+    assert_eq!(
+        why_disallowed(':', Mode::ALL),
+        Some((Metacharacter, Mode::WINDOWS_MAC))
+    );
+    assert_eq!(why_disallowed(':', Mode::HFS), Some((Separator, Mode::HFS)));
+    assert_eq!(
+        why_disallowed('*', Mode::ALL),
+        Some((Metacharacter, Mode::WINDOWS))
+    );
+    assert_eq!(
+        why_disallowed(' ', Mode::WINDOWS_END),
+        Some((Trailing, Mode::WINDOWS_END))
+    );
+    assert_eq!(why_disallowed(' ', Mode::WINDOWS), None);
+    assert_eq!(
+        why_disallowed(' ', Mode::POSIX),
+        Some((NotPortable, Mode::POSIX))
+    );
+    assert_eq!(
+        why_disallowed('ž', Mode::ASCII_ONLY | Mode::UNIX),
+        Some((NonAscii, Mode::ASCII_ONLY))
+    );
+    assert_eq!(
+        why_disallowed('\\', Mode::FAT | Mode::POSIX),
+        Some((Separator, Mode::FAT | Mode::POSIX))
+    );
+    assert_eq!(why_disallowed('a', Mode::ALL), None);
+}
+
+#[test]
+fn messages() {
+    assert_eq!(DisallowedReason::Separator.to_string(), "path separator");
+    assert_eq!(
+        DisallowedReason::Metacharacter.to_string(),
+        "Windows metacharacter"
+    );
+    assert_eq!(
+        DisallowedReason::Trailing.to_string(),
+        "reserved trailing character"
+    );
+}
+
+#[test]
+fn same_as_allowed() {
+    for mode in (0..0x100).map(Mode::from_bits_retain) {
+        for c in (0..0x100).filter_map(char::from_u32).chain(['ž', '😀']) {
+            let why = why_disallowed(c, mode);
+            assert_eq!(why.is_none(), allowed(c, mode), "{c:?} {mode}");
+            if let Some((_, systems)) = why {
+                assert!(mode.contains(systems));
+                assert!(!allowed(c, systems));
+            }
+        }
+    }
+}